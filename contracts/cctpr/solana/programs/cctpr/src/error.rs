@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use price_oracle::state::StaleProposal;
 
 #[error_code]
 pub enum CctprError {
@@ -28,4 +29,68 @@ pub enum CctprError {
 
   #[msg("Exceeds max fee")]
   ExceedsMaxFee,
+
+  #[msg("Account not migrated")]
+  AccountNotMigrated,
+
+  #[msg("Account already migrated")]
+  AccountAlreadyMigrated,
+
+  #[msg("Invalid fee share")]
+  InvalidFeeShare,
+
+  #[msg("Exceeds max integrator fee share")]
+  ExceedsMaxIntegratorFeeShare,
+
+  //no longer returned since expired discounts are ignored, kept so the later codes don't shift
+  #[msg("Fee discount expired")]
+  FeeDiscountExpired,
+
+  #[msg("Invalid transfer limits")]
+  InvalidTransferLimits,
+
+  #[msg("Transfer amount out of bounds")]
+  TransferAmountOutOfBounds,
+
+  #[msg("Transfer volume limit exceeded")]
+  TransferVolumeLimitExceeded,
+
+  #[msg("Invalid mint recipient")]
+  InvalidMintRecipient,
+
+  #[msg("Denylisted")]
+  Denylisted,
+
+  #[msg("Timelock required")]
+  TimelockRequired,
+
+  #[msg("Timelock not elapsed")]
+  TimelockNotElapsed,
+
+  #[msg("Invalid chain config")]
+  InvalidChainConfig,
+
+  #[msg("Invalid multisig")]
+  InvalidMultisig,
+
+  #[msg("Already approved")]
+  AlreadyApproved,
+
+  #[msg("Not enough approvals")]
+  NotEnoughApprovals,
+
+  #[msg("Proposal expired")]
+  ProposalExpired,
+
+  #[msg("Proposal outdated")]
+  ProposalOutdated,
+}
+
+impl From<StaleProposal> for CctprError {
+  fn from(stale: StaleProposal) -> Self {
+    match stale {
+      StaleProposal::Outdated => CctprError::ProposalOutdated,
+      StaleProposal::Expired  => CctprError::ProposalExpired,
+    }
+  }
 }
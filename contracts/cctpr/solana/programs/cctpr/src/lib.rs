@@ -1,12 +1,15 @@
 mod error;
 mod id;
 mod cctp_cpi;
+mod migration;
 mod processor;
-mod state;
+pub mod state;
 
 use anchor_lang::prelude::*;
 use processor::*;
-use state::{FeeAdjustment, FeeAdjustmentType};
+use state::{
+  DenylistKind, FeeAdjustment, FeeAdjustmentType, ProposedAccountMeta, Timelock, TimelockedAction,
+};
 
 pub use id::ID;
 
@@ -14,6 +17,7 @@ pub use id::ID;
 pub mod cctpr {
   use super::*;
 
+  #[allow(clippy::too_many_arguments)]
   pub fn transfer_with_relay(
     ctx: Context<TransferWithRelay>,
     input_amount: u64,
@@ -24,6 +28,7 @@ pub mod cctpr {
     gasless: Option<GaslessParams>,
     message_sent_event_data_seed: [u8; 4],
     message_sent_event_data_bump: u8,
+    integrator_params: OptionalIntegratorParams,
   ) -> Result<()> {
     processor::transfer_with_relay(
       ctx,
@@ -35,6 +40,7 @@ pub mod cctpr {
       gasless,
       message_sent_event_data_seed,
       message_sent_event_data_bump,
+      integrator_params,
     )
   }
 
@@ -68,7 +74,7 @@ pub mod cctpr {
 
   pub fn register_chain(
     ctx: Context<RegisterChain>,
-    domain_id: u8,
+    domain_id: u32,
     chain_id: u16,
   ) -> Result<()> {
     processor::register_chain(ctx, domain_id, chain_id)
@@ -78,6 +84,86 @@ pub mod cctpr {
     processor::deregister_chain(ctx)
   }
 
+  pub fn migrate_chain_config(ctx: Context<MigrateChainConfig>) -> Result<()> {
+    processor::migrate_chain_config(ctx)
+  }
+
+  pub fn update_price_smoothing_window(
+    ctx: Context<UpdatePriceSmoothingWindow>,
+    new_price_smoothing_window: u32,
+  ) -> Result<()> {
+    processor::update_price_smoothing_window(ctx, new_price_smoothing_window)
+  }
+
+  pub fn update_transfer_limits(
+    ctx: Context<UpdateTransferLimits>,
+    min_amount: u64,
+    max_amount: u64,
+    window_volume: u64,
+    window_duration: u32,
+  ) -> Result<()> {
+    processor::update_transfer_limits(ctx, min_amount, max_amount, window_volume, window_duration)
+  }
+
+  pub fn add_to_denylist(
+    ctx: Context<AddToDenylist>,
+    kind: DenylistKind,
+    address: [u8; 32],
+  ) -> Result<()> {
+    processor::add_to_denylist(ctx, kind, address)
+  }
+
+  pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+    processor::remove_from_denylist(ctx)
+  }
+
+  pub fn register_fee_discount(
+    ctx: Context<RegisterFeeDiscount>,
+    subject: Pubkey,
+    expiration_time: u32,
+  ) -> Result<()> {
+    processor::register_fee_discount(ctx, subject, expiration_time)
+  }
+
+  pub fn update_fee_discount(
+    ctx: Context<UpdateFeeDiscount>,
+    adjustment_type: FeeAdjustmentType,
+    new_fee_adjustment: FeeAdjustment,
+  ) -> Result<()> {
+    processor::update_fee_discount(ctx, adjustment_type, new_fee_adjustment)
+  }
+
+  pub fn update_fee_discount_expiration(
+    ctx: Context<UpdateFeeDiscount>,
+    expiration_time: u32,
+  ) -> Result<()> {
+    processor::update_fee_discount_expiration(ctx, expiration_time)
+  }
+
+  pub fn deregister_fee_discount(ctx: Context<DeregisterFeeDiscount>) -> Result<()> {
+    processor::deregister_fee_discount(ctx)
+  }
+
+  pub fn register_integrator(
+    ctx: Context<RegisterIntegrator>,
+    address: Pubkey,
+    max_fee_share_bps: u16,
+  ) -> Result<()> {
+    processor::register_integrator(ctx, address, max_fee_share_bps)
+  }
+
+  pub fn update_integrator(ctx: Context<UpdateIntegrator>, max_fee_share_bps: u16) -> Result<()> {
+    processor::update_integrator(ctx, max_fee_share_bps)
+  }
+
+  pub fn deregister_integrator(ctx: Context<DeregisterIntegrator>) -> Result<()> {
+    processor::deregister_integrator(ctx)
+  }
+
+  pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    processor::migrate(ctx)
+  }
+
   pub fn update_fee_adjustment(
     ctx: Context<UpdateFeeAdjustment>,
     adjustment_type: FeeAdjustmentType,
@@ -115,10 +201,79 @@ pub mod cctpr {
     processor::update_fee_adjuster(ctx, new_fee_adjuster)
   }
 
+  pub fn update_compliance(
+    ctx: Context<RoleUpdate>,
+    new_compliance: Pubkey,
+  ) -> Result<()> {
+    processor::update_compliance(ctx, new_compliance)
+  }
+
   pub fn update_offchain_quoter(
     ctx: Context<RoleUpdate>,
     new_offchain_quoter: [u8; 20],
   ) -> Result<()> {
     processor::update_offchain_quoter(ctx, new_offchain_quoter)
   }
+
+  pub fn update_timelock(ctx: Context<RoleUpdate>, new_timelock: Timelock) -> Result<()> {
+    processor::update_timelock(ctx, new_timelock)
+  }
+
+  // -- Timelocked actions --
+
+  pub fn propose_timelocked_action(
+    ctx: Context<ProposeTimelockedAction>,
+    action: TimelockedAction,
+  ) -> Result<()> {
+    processor::propose_timelocked_action(ctx, action)
+  }
+
+  pub fn execute_timelocked_action(ctx: Context<ExecuteTimelockedAction>) -> Result<()> {
+    processor::execute_timelocked_action(ctx)
+  }
+
+  pub fn cancel_timelocked_action(ctx: Context<CancelTimelockedAction>) -> Result<()> {
+    processor::cancel_timelocked_action(ctx)
+  }
+
+  // -- Multisig --
+
+  pub fn create_multisig(
+    ctx: Context<CreateMultisig>,
+    create_key: Pubkey,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+  ) -> Result<()> {
+    processor::create_multisig(ctx, create_key, signers, threshold)
+  }
+
+  pub fn update_multisig(
+    ctx: Context<UpdateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+  ) -> Result<()> {
+    processor::update_multisig(ctx, signers, threshold)
+  }
+
+  pub fn propose_multisig_instruction(
+    ctx: Context<ProposeMultisigInstruction>,
+    accounts: Vec<ProposedAccountMeta>,
+    data: Vec<u8>,
+  ) -> Result<()> {
+    processor::propose_multisig_instruction(ctx, accounts, data)
+  }
+
+  pub fn approve_multisig_proposal(ctx: Context<ApproveMultisigProposal>) -> Result<()> {
+    processor::approve_multisig_proposal(ctx)
+  }
+
+  pub fn execute_multisig_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteMultisigProposal<'info>>,
+  ) -> Result<()> {
+    processor::execute_multisig_proposal(ctx)
+  }
+
+  pub fn cancel_multisig_proposal(ctx: Context<CancelMultisigProposal>) -> Result<()> {
+    processor::cancel_multisig_proposal(ctx)
+  }
 }
@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use crate::{
+  error::CctprError,
+  state::{
+    Config, ChainConfig, FeeAdjustmentType, FeeDiscount, InstantChangeAllowance, LayoutVersion,
+    Timelock,
+  },
+};
+
+//every layout change has to come with a way to upgrade the previous layout(s) here, so that
+//  existing accounts can be migrated in place via the `migrate` instruction
+
+/// `Config` before it was versioned, i.e. without a version byte after the discriminator.
+/// Such accounts are identified by their size.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct ConfigV0 {
+  pub owner:           Pubkey,
+  pub pending_owner:   Pubkey,
+  pub fee_adjuster:    Pubkey,
+  pub fee_recipient:   Pubkey,
+  pub offchain_quoter: [u8; 20],
+  pub rent_bump:       u8,
+}
+
+impl From<ConfigV0> for Config {
+  fn from(v0: ConfigV0) -> Self {
+    Config {
+      version:         LayoutVersion,
+      owner:           v0.owner,
+      pending_owner:   v0.pending_owner,
+      fee_adjuster:    v0.fee_adjuster,
+      fee_recipient:   v0.fee_recipient,
+      offchain_quoter: v0.offchain_quoter,
+      rent_bump:       v0.rent_bump,
+      //i.e. the denylist stays disabled until a compliance role is set
+      compliance:      Pubkey::default(),
+      //i.e. spot prices, as before
+      price_smoothing_window: 0,
+      //i.e. all governance actions still take effect instantly
+      timelock:        Timelock::default(),
+      instant_change_allowance: InstantChangeAllowance::default(),
+      transfer_limits_enabled: false,
+    }
+  }
+}
+
+/// Deserializes the config regardless of its layout version.
+///
+/// Returns whether it had to be upgraded, i.e. whether it needs to be migrated.
+pub fn load_config(data: &[u8]) -> Result<(Config, bool)> {
+  if data.starts_with(Config::DISCRIMINATOR) && data.len() == 8 + ConfigV0::INIT_SPACE {
+    let v0 = ConfigV0::deserialize(&mut &data[8..])?;
+    return Ok((v0.into(), true));
+  }
+
+  Ok((Config::try_deserialize(&mut &data[..])?, false))
+}
+
+/// Deserializes a chain config regardless of its layout version.
+///
+/// Returns whether it had to be upgraded, i.e. whether it needs to be migrated.
+pub fn load_chain_config(data: &[u8]) -> Result<(ChainConfig, bool)> {
+  let mut chain_config = ChainConfig::try_deserialize(&mut &data[..])?;
+  let outdated = chain_config.fee_adjustment_count() < FeeAdjustmentType::COUNT;
+  if outdated {
+    chain_config.pad_fee_adjustments();
+  }
+
+  Ok((chain_config, outdated))
+}
+
+/// Deserializes a fee discount regardless of its layout version.
+///
+/// Returns whether it had to be upgraded, i.e. whether it needs to be migrated.
+pub fn load_fee_discount(data: &[u8]) -> Result<(FeeDiscount, bool)> {
+  let mut fee_discount = FeeDiscount::try_deserialize(&mut &data[..])?;
+  let outdated = fee_discount.fee_adjustment_count() < FeeAdjustmentType::COUNT;
+  if outdated {
+    fee_discount.pad_fee_adjustments();
+  }
+
+  Ok((fee_discount, outdated))
+}
+
+/// Returns the given account data upgraded to the current layout of its account type.
+pub fn migrate(data: &[u8]) -> Result<Vec<u8>> {
+  let mut migrated = Vec::new();
+  let outdated = if data.starts_with(Config::DISCRIMINATOR) {
+    let (config, outdated) = load_config(data)?;
+    config.try_serialize(&mut migrated)?;
+    outdated
+  }
+  else if data.starts_with(ChainConfig::DISCRIMINATOR) {
+    let (chain_config, outdated) = load_chain_config(data)?;
+    chain_config.try_serialize(&mut migrated)?;
+    outdated
+  }
+  else if data.starts_with(FeeDiscount::DISCRIMINATOR) {
+    let (fee_discount, outdated) = load_fee_discount(data)?;
+    fee_discount.try_serialize(&mut migrated)?;
+    outdated
+  }
+  else {
+    return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+  };
+
+  require!(outdated, CctprError::AccountAlreadyMigrated);
+  Ok(migrated)
+}
+
+#[cfg(test)]
+fn serialize_account<T: AnchorSerialize>(discriminator: &[u8], account: &T) -> Vec<u8> {
+  let mut data = discriminator.to_vec();
+  account.serialize(&mut data).unwrap();
+  data
+}
+
+#[test]
+fn outdated_accounts_are_migrated() {
+  use crate::state::{
+    with_account_info, AccountView, AnyChainConfig, FeeAdjustment, ZeroCopyChainConfig,
+    ZeroCopyConfig, BASIS_POINTS,
+  };
+
+  let owner = Pubkey::new_unique();
+  let fee_recipient = Pubkey::new_unique();
+  let subject = Pubkey::new_unique();
+  let adjustment = FeeAdjustment { absolute_usd: -5, relative_percent_bps: 10_000 };
+  //i.e. created when there were fewer fee adjustment types
+  let outdated_adjustments = vec![adjustment.clone(); FeeAdjustmentType::COUNT - 1];
+
+  let outdated_config = serialize_account(Config::DISCRIMINATOR, &ConfigV0 {
+    owner,
+    pending_owner: Pubkey::default(),
+    fee_adjuster: owner,
+    fee_recipient,
+    offchain_quoter: [7; 20],
+    rent_bump: 254,
+  });
+  let outdated_chain_config = serialize_account(ChainConfig::DISCRIMINATOR, &(
+    ChainConfig::VERSION,
+    7_u32,
+    3_u16,
+    outdated_adjustments.clone(),
+  ));
+
+  type Check<'a> = Box<dyn Fn(&[u8]) + 'a>;
+  let cases: Vec<(&str, Vec<u8>, usize, Check)> = vec![
+    (
+      "unversioned config",
+      outdated_config.clone(),
+      8 + Config::INIT_SPACE,
+      Box::new(|migrated| {
+        assert_eq!(migrated[8], Config::VERSION);
+        let config = Config::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(config.owner, owner);
+        assert_eq!(config.pending_owner, Pubkey::default());
+        assert_eq!(config.fee_adjuster, owner);
+        assert_eq!(config.fee_recipient, fee_recipient);
+        assert_eq!(config.offchain_quoter, [7; 20]);
+        assert_eq!(config.rent_bump, 254);
+        assert_eq!(config.compliance, Pubkey::default());
+        assert_eq!(config.price_smoothing_window, 0);
+        assert!(!config.timelock.is_enabled());
+        assert!(!config.transfer_limits_enabled);
+      }),
+    ),
+    (
+      "chain config with fewer fee adjustments",
+      outdated_chain_config.clone(),
+      8 + ChainConfig::INIT_SPACE,
+      Box::new(|migrated| {
+        let mut chain_config = ChainConfig::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(chain_config.domain_id, 7);
+        assert_eq!(chain_config.chain_id, 3);
+        assert_eq!(chain_config.fee_adjustment_count(), FeeAdjustmentType::COUNT);
+        assert_eq!(chain_config.get_fee_adjustment(FeeAdjustmentType::AvaxHop), &adjustment);
+        assert!(
+          chain_config.set_fee_adjustment(FeeAdjustmentType::GasDropoff, Default::default()).is_ok()
+        );
+      }),
+    ),
+    (
+      "fee discount with fewer fee adjustments",
+      serialize_account(FeeDiscount::DISCRIMINATOR, &(
+        FeeDiscount::VERSION,
+        subject,
+        1_000_u32,
+        outdated_adjustments.clone(),
+      )),
+      8 + FeeDiscount::INIT_SPACE,
+      Box::new(|migrated| {
+        let fee_discount = FeeDiscount::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(fee_discount.subject, subject);
+        assert_eq!(fee_discount.expiration_time, 1_000);
+        assert_eq!(fee_discount.fee_adjustment_count(), FeeAdjustmentType::COUNT);
+        assert_eq!(fee_discount.get_fee_adjustment(FeeAdjustmentType::AvaxHop), &adjustment);
+        assert_eq!(
+          fee_discount.get_fee_adjustment(FeeAdjustmentType::GasDropoff),
+          &FeeAdjustment { absolute_usd: 0, relative_percent_bps: BASIS_POINTS as u32 },
+        );
+      }),
+    ),
+  ];
+
+  for (name, outdated, migrated_len, check) in cases {
+    let migrated = migrate(&outdated).unwrap_or_else(|e| panic!("{name}: {e}"));
+    assert_eq!(migrated.len(), migrated_len, "{name}");
+    check(&migrated);
+
+    //migrating twice is rejected
+    assert_eq!(
+      migrate(&migrated).unwrap_err(),
+      CctprError::AccountAlreadyMigrated.into(),
+      "{name}",
+    );
+
+    //unknown versions are rejected
+    let mut unknown = migrated.clone();
+    unknown[8] += 1;
+    assert!(migrate(&unknown).is_err(), "{name}");
+  }
+
+  //outdated chain configs can still be configured
+  let any_chain_config =
+    AnyChainConfig::try_deserialize(&mut outdated_chain_config.as_slice()).unwrap();
+  assert_eq!(
+    any_chain_config.get_fee_adjustment(FeeAdjustmentType::GasDropoff),
+    &FeeAdjustment::default(),
+  );
+  assert_eq!(any_chain_config.get_fee_adjustment(FeeAdjustmentType::AvaxHop), &adjustment);
+
+  //and used for transfers, which read them in place once they are migrated and see the same
+  //  upgraded accounts until then
+  for config in [outdated_config.clone(), migrate(&outdated_config).unwrap()] {
+    with_account_info(&config, |account| {
+      let config = ZeroCopyConfig::view(account).unwrap();
+      assert_eq!(
+        matches!(config, AccountView::InPlace(_)),
+        account.data_len() == 8 + Config::INIT_SPACE,
+      );
+      assert_eq!(config.fee_recipient(), fee_recipient);
+      assert_eq!(config.offchain_quoter(), [7; 20]);
+      assert_eq!(config.rent_bump(), 254);
+      assert_eq!(config.compliance(), Pubkey::default());
+      assert_eq!(config.price_smoothing_window(), 0);
+      assert!(!config.transfer_limits_enabled());
+    });
+  }
+  for chain_config in [outdated_chain_config.clone(), migrate(&outdated_chain_config).unwrap()] {
+    with_account_info(&chain_config, |account| {
+      let chain_config = ZeroCopyChainConfig::view(account).unwrap();
+      assert_eq!(
+        matches!(chain_config, AccountView::InPlace(_)),
+        account.data_len() == 8 + ChainConfig::INIT_SPACE,
+      );
+      assert_eq!(chain_config.domain_id(), 7);
+      assert_eq!(chain_config.get_fee_adjustment(FeeAdjustmentType::AvaxHop), adjustment);
+      assert_eq!(
+        chain_config.get_fee_adjustment(FeeAdjustmentType::GasDropoff),
+        FeeAdjustment::default(),
+      );
+    });
+  }
+}
@@ -1,6 +1,185 @@
-use anchor_lang::prelude::*;
-use crate::state::{Config, ChainConfig, FeeAdjustment, FeeAdjustmentType};
-use crate::error::CctprError;
+use anchor_lang::{prelude::*, system_program};
+use crate::state::{
+  Config, ChainConfig, AnyChainConfig, LegacyChainConfig, FeeAdjustment, FeeAdjustmentType,
+  LayoutVersion, Integrator, FeeDiscount, TransferLimits, DenylistEntry, DenylistKind, Timelock,
+  InstantChangeAllowance, TimelockedAction, TimelockProposal, BASIS_POINTS,
+};
+use crate::{error::CctprError, migration};
+
+// -- Events --
+
+#[event]
+pub struct Initialized {
+  pub owner: Pubkey,
+  pub fee_adjuster: Pubkey,
+  pub fee_recipient: Pubkey,
+  pub offchain_quoter: [u8; 20],
+}
+
+#[event]
+pub struct PendingOwnerUpdated {
+  pub old_pending_owner: Pubkey,
+  pub new_pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnerUpdated {
+  pub old_owner: Pubkey,
+  pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct ChainRegistered {
+  pub domain_id: u32,
+  pub chain_id: u16,
+}
+
+#[event]
+pub struct ChainDeregistered {
+  pub domain_id: u32,
+  pub chain_id: u16,
+}
+
+#[event]
+pub struct ChainConfigMigrated {
+  pub domain_id: u32,
+}
+
+#[event]
+pub struct FeeAdjustmentUpdated {
+  pub domain_id: u32,
+  pub adjustment_type: FeeAdjustmentType,
+  pub old_fee_adjustment: FeeAdjustment,
+  pub new_fee_adjustment: FeeAdjustment,
+}
+
+#[event]
+pub struct PriceSmoothingWindowUpdated {
+  pub old_price_smoothing_window: u32,
+  pub new_price_smoothing_window: u32,
+}
+
+#[event]
+pub struct TransferLimitsUpdated {
+  pub domain_id: u32,
+  pub old_min_amount: u64,
+  pub new_min_amount: u64,
+  pub old_max_amount: u64,
+  pub new_max_amount: u64,
+  pub old_window_volume: u64,
+  pub new_window_volume: u64,
+  pub old_window_duration: u32,
+  pub new_window_duration: u32,
+}
+
+#[event]
+pub struct DenylistUpdated {
+  pub kind: DenylistKind,
+  pub address: [u8; 32],
+  pub is_denylisted: bool,
+}
+
+#[event]
+pub struct FeeDiscountRegistered {
+  pub subject: Pubkey,
+  pub expiration_time: u32,
+}
+
+#[event]
+pub struct FeeDiscountUpdated {
+  pub subject: Pubkey,
+  pub adjustment_type: FeeAdjustmentType,
+  pub old_fee_adjustment: FeeAdjustment,
+  pub new_fee_adjustment: FeeAdjustment,
+}
+
+#[event]
+pub struct FeeDiscountExpirationUpdated {
+  pub subject: Pubkey,
+  pub old_expiration_time: u32,
+  pub new_expiration_time: u32,
+}
+
+#[event]
+pub struct FeeDiscountDeregistered {
+  pub subject: Pubkey,
+}
+
+#[event]
+pub struct IntegratorRegistered {
+  pub address: Pubkey,
+  pub max_fee_share_bps: u16,
+}
+
+#[event]
+pub struct IntegratorUpdated {
+  pub address: Pubkey,
+  pub old_max_fee_share_bps: u16,
+  pub new_max_fee_share_bps: u16,
+}
+
+#[event]
+pub struct IntegratorDeregistered {
+  pub address: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+  pub account: Pubkey,
+  pub old_size: u32,
+  pub new_size: u32,
+}
+
+#[event]
+pub struct FeeRecipientUpdated {
+  pub old_fee_recipient: Pubkey,
+  pub new_fee_recipient: Pubkey,
+}
+
+#[event]
+pub struct FeeAdjusterUpdated {
+  pub old_fee_adjuster: Pubkey,
+  pub new_fee_adjuster: Pubkey,
+}
+
+#[event]
+pub struct ComplianceUpdated {
+  pub old_compliance: Pubkey,
+  pub new_compliance: Pubkey,
+}
+
+#[event]
+pub struct OffchainQuoterUpdated {
+  pub old_offchain_quoter: [u8; 20],
+  pub new_offchain_quoter: [u8; 20],
+}
+
+#[event]
+pub struct TimelockUpdated {
+  pub old_timelock: Timelock,
+  pub new_timelock: Timelock,
+}
+
+#[event]
+pub struct TimelockedActionProposed {
+  pub proposal:      Pubkey,
+  pub proposer:      Pubkey,
+  pub executable_at: i64,
+  pub expires_at:    i64,
+  pub action:        TimelockedAction,
+}
+
+#[event]
+pub struct TimelockedActionExecuted {
+  pub proposal: Pubkey,
+  pub action:   TimelockedAction,
+}
+
+#[event]
+pub struct TimelockedActionCancelled {
+  pub proposal: Pubkey,
+  pub action:   TimelockedAction,
+}
 
 // -- Initialize --
 
@@ -36,14 +215,21 @@ pub fn initialize(
   require!(fee_recipient != Pubkey::default(), CctprError::InvalidFeeRecipient);
 
   ctx.accounts.config.set_inner(Config {
+    version: LayoutVersion,
     owner,
     pending_owner: Pubkey::default(),
     fee_adjuster,
     fee_recipient,
     offchain_quoter,
     rent_bump: ctx.bumps.rent_custodian,
+    compliance: Pubkey::default(),
+    price_smoothing_window: 0,
+    timelock: Timelock::default(),
+    instant_change_allowance: InstantChangeAllowance::default(),
+    transfer_limits_enabled: false,
   });
 
+  emit!(Initialized { owner, fee_adjuster, fee_recipient, offchain_quoter });
   Ok(())
 }
 
@@ -61,15 +247,20 @@ pub fn submit_owner_transfer_request(
   ctx: Context<OwnerContext>,
   new_owner: Pubkey,
 ) -> Result<()> {
-  ctx.accounts.config.pending_owner = new_owner;
+  update_pending_owner(&mut ctx.accounts.config, new_owner);
   Ok(())
 }
 
 pub fn cancel_owner_transfer_request(ctx: Context<OwnerContext>) -> Result<()> {
-  ctx.accounts.config.pending_owner = Pubkey::default();
+  update_pending_owner(&mut ctx.accounts.config, Pubkey::default());
   Ok(())
 }
 
+fn update_pending_owner(config: &mut Config, new_pending_owner: Pubkey) {
+  let old_pending_owner = std::mem::replace(&mut config.pending_owner, new_pending_owner);
+  emit!(PendingOwnerUpdated { old_pending_owner, new_pending_owner });
+}
+
 #[derive(Accounts)]
 pub struct ConfirmOwnerTransfer<'info> {
   pub pending_owner: Signer<'info>,
@@ -79,15 +270,17 @@ pub struct ConfirmOwnerTransfer<'info> {
 }
 
 pub fn confirm_owner_transfer_request(ctx: Context<ConfirmOwnerTransfer>) -> Result<()> {
-  ctx.accounts.config.owner = ctx.accounts.pending_owner.key();
-  ctx.accounts.config.pending_owner = Pubkey::default();
+  let new_owner = ctx.accounts.pending_owner.key();
+  let old_owner = std::mem::replace(&mut ctx.accounts.config.owner, new_owner);
+  update_pending_owner(&mut ctx.accounts.config, Pubkey::default());
+  emit!(OwnerUpdated { old_owner, new_owner });
   Ok(())
 }
 
 // -- EVM chain config --
 
 #[derive(Accounts)]
-#[instruction(domain_id: u8)]
+#[instruction(domain_id: u32)]
 pub struct RegisterChain<'info> {
   #[account(mut)]
   pub owner: Signer<'info>,
@@ -109,11 +302,11 @@ pub struct RegisterChain<'info> {
 
 pub fn register_chain(
   ctx: Context<RegisterChain>,
-  domain_id: u8,
+  domain_id: u32,
   chain_id: u16,
 ) -> Result<()> {
-  ctx.accounts.chain_config.domain_id = domain_id;
-  ctx.accounts.chain_config.chain_id = chain_id;
+  ctx.accounts.chain_config.set_inner(ChainConfig::new(domain_id, chain_id));
+  emit!(ChainRegistered { domain_id, chain_id });
   Ok(())
 }
 
@@ -131,7 +324,48 @@ pub struct DeregisterChain<'info> {
   pub system_program: Program<'info, System>,
 }
 
-pub fn deregister_chain(_ctx: Context<DeregisterChain>) -> Result<()> {
+pub fn deregister_chain(ctx: Context<DeregisterChain>) -> Result<()> {
+  let chain_config = &ctx.accounts.chain_config;
+  emit!(ChainDeregistered { domain_id: chain_config.domain_id, chain_id: chain_config.chain_id });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateChainConfig<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  #[account(has_one = owner @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(
+    mut,
+    close = owner,
+    seeds = [LegacyChainConfig::SEED_PREFIX, legacy_chain_config.domain_id.to_be_bytes().as_ref()],
+    bump
+  )]
+  pub legacy_chain_config: Account<'info, LegacyChainConfig>,
+
+  #[account(
+    init,
+    payer = owner,
+    space = 8 + ChainConfig::INIT_SPACE,
+    seeds = [
+      ChainConfig::SEED_PREFIX,
+      u32::from(legacy_chain_config.domain_id).to_be_bytes().as_ref()
+    ],
+    bump
+  )]
+  pub chain_config: Account<'info, ChainConfig>,
+
+  pub system_program: Program<'info, System>,
+}
+
+//moves a chain config from its legacy u8-domain address to its u32-domain address
+pub fn migrate_chain_config(ctx: Context<MigrateChainConfig>) -> Result<()> {
+  let migrated = ChainConfig::from(&*ctx.accounts.legacy_chain_config);
+  emit!(ChainConfigMigrated { domain_id: migrated.domain_id });
+  ctx.accounts.chain_config.set_inner(migrated);
   Ok(())
 }
 
@@ -143,6 +377,7 @@ pub struct UpdateFeeAdjustment<'info> {
   )]
   pub signer: Signer<'info>,
 
+  #[account(mut)]
   pub config: Account<'info, Config>,
 
   #[account(mut)]
@@ -154,7 +389,396 @@ pub fn update_fee_adjustment(
   adjustment_type: FeeAdjustmentType,
   new_fee_adjustment: FeeAdjustment,
 ) -> Result<()> {
-  ctx.accounts.chain_config.set_fee_adjustment(adjustment_type, new_fee_adjustment);
+  let chain_config = &mut ctx.accounts.chain_config;
+  let config = &mut ctx.accounts.config;
+  let timelock = config.timelock;
+  timelock.consume_instant_change(
+    &mut config.instant_change_allowance,
+    chain_config.get_fee_adjustment(adjustment_type),
+    &new_fee_adjustment,
+    Clock::get()?.unix_timestamp,
+  )?;
+  set_fee_adjustment(chain_config, adjustment_type, new_fee_adjustment)
+}
+
+fn set_fee_adjustment(
+  chain_config: &mut ChainConfig,
+  adjustment_type: FeeAdjustmentType,
+  new_fee_adjustment: FeeAdjustment,
+) -> Result<()> {
+  let old_fee_adjustment = chain_config.get_fee_adjustment(adjustment_type).clone();
+  chain_config.set_fee_adjustment(adjustment_type, new_fee_adjustment.clone())?;
+  emit!(FeeAdjustmentUpdated {
+    domain_id: chain_config.domain_id,
+    adjustment_type,
+    old_fee_adjustment,
+    new_fee_adjustment,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceSmoothingWindow<'info> {
+  #[account(constraint =
+    signer.key() == config.owner ||
+    signer.key() == config.fee_adjuster @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  #[account(mut)]
+  pub config: Account<'info, Config>,
+}
+
+//the price history of every destination (and of avalanche) has to be initialized in the price
+//  oracle before enabling this, otherwise on-chain quotes fail
+pub fn update_price_smoothing_window(
+  ctx: Context<UpdatePriceSmoothingWindow>,
+  new_price_smoothing_window: u32,
+) -> Result<()> {
+  let old_price_smoothing_window = std::mem::replace(
+    &mut ctx.accounts.config.price_smoothing_window,
+    new_price_smoothing_window
+  );
+  emit!(PriceSmoothingWindowUpdated { old_price_smoothing_window, new_price_smoothing_window });
+  Ok(())
+}
+
+// -- Transfer limits --
+
+//transfers to a chain are unlimited until its limits have been set
+#[derive(Accounts)]
+pub struct UpdateTransferLimits<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  #[account(mut, has_one = owner @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  //might not have been migrated yet
+  pub chain_config: Account<'info, AnyChainConfig>,
+
+  #[account(
+    init_if_needed,
+    payer = owner,
+    space = 8 + TransferLimits::INIT_SPACE,
+    seeds = [TransferLimits::SEED_PREFIX, chain_config.domain_id.to_be_bytes().as_ref()],
+    bump
+  )]
+  pub transfer_limits: Account<'info, TransferLimits>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn update_transfer_limits(
+  ctx: Context<UpdateTransferLimits>,
+  min_amount: u64,
+  max_amount: u64,
+  window_volume: u64,
+  window_duration: u32,
+) -> Result<()> {
+  let now = Clock::get()?.unix_timestamp as u32;
+  let transfer_limits = &mut ctx.accounts.transfer_limits;
+  let (old_min_amount, old_max_amount, old_window_volume, old_window_duration) = (
+    transfer_limits.min_amount,
+    transfer_limits.max_amount,
+    transfer_limits.window_volume,
+    transfer_limits.window_duration,
+  );
+  transfer_limits.domain_id = ctx.accounts.chain_config.domain_id;
+  transfer_limits.set(min_amount, max_amount, window_volume, window_duration, now)?;
+  ctx.accounts.config.transfer_limits_enabled = true;
+  emit!(TransferLimitsUpdated {
+    domain_id: transfer_limits.domain_id,
+    old_min_amount,
+    new_min_amount: min_amount,
+    old_max_amount,
+    new_max_amount: max_amount,
+    old_window_volume,
+    new_window_volume: window_volume,
+    old_window_duration,
+    new_window_duration: window_duration,
+  });
+  Ok(())
+}
+
+// -- Denylist --
+
+#[derive(Accounts)]
+#[instruction(kind: DenylistKind, address: [u8; 32])]
+pub struct AddToDenylist<'info> {
+  #[account(mut)]
+  pub compliance: Signer<'info>,
+
+  #[account(has_one = compliance @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(
+    init,
+    payer = compliance,
+    space = 8 + DenylistEntry::INIT_SPACE,
+    seeds = [DenylistEntry::SEED_PREFIX, &[kind as u8], address.as_ref()],
+    bump
+  )]
+  pub denylist_entry: Account<'info, DenylistEntry>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn add_to_denylist(
+  ctx: Context<AddToDenylist>,
+  kind: DenylistKind,
+  address: [u8; 32],
+) -> Result<()> {
+  ctx.accounts.denylist_entry.set_inner(DenylistEntry { version: LayoutVersion, kind, address });
+  emit!(DenylistUpdated { kind, address, is_denylisted: true });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+  #[account(mut)]
+  pub compliance: Signer<'info>,
+
+  #[account(has_one = compliance @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(mut, close = compliance)]
+  pub denylist_entry: Account<'info, DenylistEntry>,
+}
+
+pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+  let denylist_entry = &ctx.accounts.denylist_entry;
+  emit!(DenylistUpdated {
+    kind: denylist_entry.kind,
+    address: denylist_entry.address,
+    is_denylisted: false,
+  });
+  Ok(())
+}
+
+// -- Fee discounts --
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct RegisterFeeDiscount<'info> {
+  #[account(mut, constraint =
+    signer.key() == config.owner ||
+    signer.key() == config.fee_adjuster @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub config: Account<'info, Config>,
+
+  #[account(
+    init,
+    payer = signer,
+    space = 8 + FeeDiscount::INIT_SPACE,
+    seeds = [FeeDiscount::SEED_PREFIX, subject.as_ref()],
+    bump
+  )]
+  pub fee_discount: Account<'info, FeeDiscount>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn register_fee_discount(
+  ctx: Context<RegisterFeeDiscount>,
+  subject: Pubkey,
+  expiration_time: u32,
+) -> Result<()> {
+  ctx.accounts.fee_discount.set_inner(FeeDiscount::new(subject, expiration_time));
+  emit!(FeeDiscountRegistered { subject, expiration_time });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeDiscount<'info> {
+  #[account(mut, constraint =
+    signer.key() == config.owner ||
+    signer.key() == config.fee_adjuster @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub config: Account<'info, Config>,
+
+  #[account(mut)]
+  pub fee_discount: Account<'info, FeeDiscount>,
+}
+
+pub fn update_fee_discount(
+  ctx: Context<UpdateFeeDiscount>,
+  adjustment_type: FeeAdjustmentType,
+  new_fee_adjustment: FeeAdjustment,
+) -> Result<()> {
+  let fee_discount = &mut ctx.accounts.fee_discount;
+  let old_fee_adjustment = fee_discount.get_fee_adjustment(adjustment_type).clone();
+  fee_discount.set_fee_adjustment(adjustment_type, new_fee_adjustment.clone())?;
+  emit!(FeeDiscountUpdated {
+    subject: fee_discount.subject,
+    adjustment_type,
+    old_fee_adjustment,
+    new_fee_adjustment,
+  });
+  Ok(())
+}
+
+pub fn update_fee_discount_expiration(
+  ctx: Context<UpdateFeeDiscount>,
+  expiration_time: u32,
+) -> Result<()> {
+  let fee_discount = &mut ctx.accounts.fee_discount;
+  let old_expiration_time = std::mem::replace(&mut fee_discount.expiration_time, expiration_time);
+  emit!(FeeDiscountExpirationUpdated {
+    subject: fee_discount.subject,
+    old_expiration_time,
+    new_expiration_time: expiration_time,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeregisterFeeDiscount<'info> {
+  #[account(mut, constraint =
+    signer.key() == config.owner ||
+    signer.key() == config.fee_adjuster @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub config: Account<'info, Config>,
+
+  #[account(mut, close = signer)]
+  pub fee_discount: Account<'info, FeeDiscount>,
+}
+
+pub fn deregister_fee_discount(ctx: Context<DeregisterFeeDiscount>) -> Result<()> {
+  emit!(FeeDiscountDeregistered { subject: ctx.accounts.fee_discount.subject });
+  Ok(())
+}
+
+// -- Integrators --
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct RegisterIntegrator<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  #[account(has_one = owner @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(
+    init,
+    payer = owner,
+    space = 8 + Integrator::INIT_SPACE,
+    seeds = [Integrator::SEED_PREFIX, address.as_ref()],
+    bump
+  )]
+  pub integrator: Account<'info, Integrator>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn register_integrator(
+  ctx: Context<RegisterIntegrator>,
+  address: Pubkey,
+  max_fee_share_bps: u16,
+) -> Result<()> {
+  require!(u64::from(max_fee_share_bps) <= BASIS_POINTS, CctprError::InvalidFeeShare);
+  ctx.accounts.integrator.set_inner(Integrator {
+    version: LayoutVersion,
+    address,
+    max_fee_share_bps,
+  });
+  emit!(IntegratorRegistered { address, max_fee_share_bps });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateIntegrator<'info> {
+  pub owner: Signer<'info>,
+
+  #[account(has_one = owner @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(mut)]
+  pub integrator: Account<'info, Integrator>,
+}
+
+pub fn update_integrator(ctx: Context<UpdateIntegrator>, max_fee_share_bps: u16) -> Result<()> {
+  require!(u64::from(max_fee_share_bps) <= BASIS_POINTS, CctprError::InvalidFeeShare);
+  let integrator = &mut ctx.accounts.integrator;
+  let old_max_fee_share_bps =
+    std::mem::replace(&mut integrator.max_fee_share_bps, max_fee_share_bps);
+  emit!(IntegratorUpdated {
+    address: integrator.address,
+    old_max_fee_share_bps,
+    new_max_fee_share_bps: max_fee_share_bps,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeregisterIntegrator<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  #[account(has_one = owner @ CctprError::NotAuthorized)]
+  pub config: Account<'info, Config>,
+
+  #[account(mut, close = owner)]
+  pub integrator: Account<'info, Integrator>,
+}
+
+pub fn deregister_integrator(ctx: Context<DeregisterIntegrator>) -> Result<()> {
+  emit!(IntegratorDeregistered { address: ctx.accounts.integrator.address });
+  Ok(())
+}
+
+// -- Account layout migrations --
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  /// CHECK: deserialized manually since it might be outdated itself
+  #[account(seeds = [Config::SEED_PREFIX], bump)]
+  pub config: UncheckedAccount<'info>,
+
+  /// CHECK: the config itself, a chain config or a fee discount, see `migration::migrate`
+  #[account(mut, owner = crate::ID)]
+  pub account: UncheckedAccount<'info>,
+
+  pub system_program: Program<'info, System>,
+}
+
+//upgrades an account to the current layout of its type in place
+pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+  let (config, _) = migration::load_config(&ctx.accounts.config.try_borrow_data()?)?;
+  require_keys_eq!(config.owner, ctx.accounts.owner.key(), CctprError::NotAuthorized);
+
+  let account = ctx.accounts.account.to_account_info();
+  let migrated = migration::migrate(&account.try_borrow_data()?)?;
+
+  let missing_rent =
+    Rent::get()?.minimum_balance(migrated.len()).saturating_sub(account.lamports());
+  if missing_rent > 0 {
+    system_program::transfer(
+      CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+          from: ctx.accounts.owner.to_account_info(),
+          to:   account.clone(),
+        },
+      ),
+      missing_rent,
+    )?;
+  }
+
+  let old_size = account.data_len() as u32;
+  account.resize(migrated.len())?;
+  account.try_borrow_mut_data()?.copy_from_slice(&migrated);
+  emit!(AccountMigrated { account: account.key(), old_size, new_size: migrated.len() as u32 });
   Ok(())
 }
 
@@ -172,8 +796,14 @@ pub fn update_fee_recipient(
   ctx: Context<RoleUpdate>,
   new_fee_recipient: Pubkey,
 ) -> Result<()> {
+  require!(!ctx.accounts.config.timelock.is_enabled(), CctprError::TimelockRequired);
+  set_fee_recipient(&mut ctx.accounts.config, new_fee_recipient)
+}
+
+fn set_fee_recipient(config: &mut Config, new_fee_recipient: Pubkey) -> Result<()> {
   require!(new_fee_recipient != Pubkey::default(), CctprError::InvalidFeeRecipient);
-  ctx.accounts.config.fee_recipient = new_fee_recipient;
+  let old_fee_recipient = std::mem::replace(&mut config.fee_recipient, new_fee_recipient);
+  emit!(FeeRecipientUpdated { old_fee_recipient, new_fee_recipient });
   Ok(())
 }
 
@@ -181,7 +811,17 @@ pub fn update_fee_adjuster(
   ctx: Context<RoleUpdate>,
   new_fee_adjuster: Pubkey,
 ) -> Result<()> {
-  ctx.accounts.config.fee_adjuster = new_fee_adjuster;
+  let old_fee_adjuster = std::mem::replace(&mut ctx.accounts.config.fee_adjuster, new_fee_adjuster);
+  emit!(FeeAdjusterUpdated { old_fee_adjuster, new_fee_adjuster });
+  Ok(())
+}
+
+pub fn update_compliance(
+  ctx: Context<RoleUpdate>,
+  new_compliance: Pubkey,
+) -> Result<()> {
+  let old_compliance = std::mem::replace(&mut ctx.accounts.config.compliance, new_compliance);
+  emit!(ComplianceUpdated { old_compliance, new_compliance });
   Ok(())
 }
 
@@ -189,6 +829,154 @@ pub fn update_offchain_quoter(
   ctx: Context<RoleUpdate>,
   new_offchain_quoter: [u8; 20],
 ) -> Result<()> {
-  ctx.accounts.config.offchain_quoter = new_offchain_quoter;
+  require!(!ctx.accounts.config.timelock.is_enabled(), CctprError::TimelockRequired);
+  set_offchain_quoter(&mut ctx.accounts.config, new_offchain_quoter);
+  Ok(())
+}
+
+fn set_offchain_quoter(config: &mut Config, new_offchain_quoter: [u8; 20]) {
+  let old_offchain_quoter = std::mem::replace(&mut config.offchain_quoter, new_offchain_quoter);
+  emit!(OffchainQuoterUpdated { old_offchain_quoter, new_offchain_quoter });
+}
+
+//tightening the timelock takes effect instantly, loosening it has to be proposed
+pub fn update_timelock(ctx: Context<RoleUpdate>, new_timelock: Timelock) -> Result<()> {
+  require!(
+    !ctx.accounts.config.timelock.is_loosened_by(&new_timelock),
+    CctprError::TimelockRequired
+  );
+  set_timelock(&mut ctx.accounts.config, new_timelock);
+  Ok(())
+}
+
+fn set_timelock(config: &mut Config, new_timelock: Timelock) {
+  let old_timelock = std::mem::replace(&mut config.timelock, new_timelock);
+  emit!(TimelockUpdated { old_timelock, new_timelock });
+}
+
+// -- Timelocked actions --
+
+#[derive(Accounts)]
+#[instruction(action: TimelockedAction)]
+pub struct ProposeTimelockedAction<'info> {
+  #[account(mut, constraint =
+    config.may_propose(&signer.key(), &action) @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub config: Account<'info, Config>,
+
+  #[account(
+    init,
+    payer = signer,
+    space = 8 + TimelockProposal::INIT_SPACE,
+    seeds = [TimelockProposal::SEED_PREFIX, action.seed().as_ref()],
+    bump,
+  )]
+  pub proposal: Account<'info, TimelockProposal>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn propose_timelocked_action(
+  ctx: Context<ProposeTimelockedAction>,
+  action: TimelockedAction,
+) -> Result<()> {
+  if let TimelockedAction::UpdateFeeRecipient { new_fee_recipient } = &action {
+    require!(*new_fee_recipient != Pubkey::default(), CctprError::InvalidFeeRecipient);
+  }
+
+  let proposer = ctx.accounts.signer.key();
+  let executable_at =
+    Clock::get()?.unix_timestamp + i64::from(ctx.accounts.config.timelock.delay);
+  let expires_at = executable_at + TimelockProposal::EXECUTION_PERIOD;
+  ctx.accounts.proposal.set_inner(TimelockProposal {
+    version: LayoutVersion,
+    proposer,
+    executable_at,
+    expires_at,
+    action: action.clone(),
+  });
+  emit!(TimelockedActionProposed {
+    proposal: ctx.accounts.proposal.key(),
+    proposer,
+    executable_at,
+    expires_at,
+    action,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTimelockedAction<'info> {
+  #[account(mut)]
+  pub config: Account<'info, Config>,
+
+  #[account(mut, close = proposer, has_one = proposer)]
+  pub proposal: Account<'info, TimelockProposal>,
+
+  /// CHECK: gets the rent of the proposal back
+  #[account(mut)]
+  pub proposer: UncheckedAccount<'info>,
+
+  //only required to update a fee adjustment
+  #[account(mut)]
+  pub chain_config: Option<Account<'info, ChainConfig>>,
+}
+
+//permissionless, the delay is what protects the action
+pub fn execute_timelocked_action(ctx: Context<ExecuteTimelockedAction>) -> Result<()> {
+  let proposal = &ctx.accounts.proposal;
+  let now = Clock::get()?.unix_timestamp;
+  require!(now >= proposal.executable_at, CctprError::TimelockNotElapsed);
+  require!(!proposal.is_expired(now), CctprError::ProposalExpired);
+
+  let config = &mut ctx.accounts.config;
+  //e.g. the proposer might no longer be the fee adjuster
+  require!(config.may_propose(&proposal.proposer, &proposal.action), CctprError::NotAuthorized);
+
+  match proposal.action.clone() {
+    TimelockedAction::UpdateOffchainQuoter { new_offchain_quoter } =>
+      set_offchain_quoter(config, new_offchain_quoter),
+    TimelockedAction::UpdateFeeRecipient { new_fee_recipient } =>
+      set_fee_recipient(config, new_fee_recipient)?,
+    TimelockedAction::UpdateFeeAdjustment { domain_id, adjustment_type, new_fee_adjustment } => {
+      let chain_config = ctx.accounts.chain_config
+        .as_mut()
+        .filter(|chain_config| chain_config.domain_id == domain_id)
+        .ok_or(CctprError::InvalidChainConfig)?;
+      set_fee_adjustment(chain_config, adjustment_type, new_fee_adjustment)?;
+    }
+    TimelockedAction::UpdateTimelock { new_timelock } =>
+      set_timelock(config, new_timelock),
+  }
+
+  emit!(TimelockedActionExecuted { proposal: proposal.key(), action: proposal.action.clone() });
+  Ok(())
+}
+
+//expired proposals can be cancelled by anyone, since they'd block new proposals otherwise
+#[derive(Accounts)]
+pub struct CancelTimelockedAction<'info> {
+  #[account(constraint =
+    signer.key() == config.owner ||
+    signer.key() == proposal.proposer ||
+    proposal.is_expired(Clock::get()?.unix_timestamp) @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub config: Account<'info, Config>,
+
+  #[account(mut, close = proposer, has_one = proposer)]
+  pub proposal: Account<'info, TimelockProposal>,
+
+  /// CHECK: gets the rent of the proposal back
+  #[account(mut)]
+  pub proposer: UncheckedAccount<'info>,
+}
+
+pub fn cancel_timelocked_action(ctx: Context<CancelTimelockedAction>) -> Result<()> {
+  let proposal = &ctx.accounts.proposal;
+  emit!(TimelockedActionCancelled { proposal: proposal.key(), action: proposal.action.clone() });
   Ok(())
 }
@@ -4,5 +4,8 @@ pub use governance::*;
 pub mod transfer_with_relay;
 pub use transfer_with_relay::*;
 
+pub mod multisig;
+pub use multisig::*;
+
 pub mod reclaim_rent;
 pub use reclaim_rent::*;
@@ -0,0 +1,284 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use crate::state::{LayoutVersion, Multisig, MultisigProposal, ProposedAccountMeta};
+use crate::error::CctprError;
+
+//switching between a single key and a multisig owner is a regular owner transfer: the pending
+//  owner is confirmed by the new key, or by a multisig proposal executing the confirmation
+
+// -- Events --
+
+//emitted when a multisig is created or its signers are changed
+#[event]
+pub struct MultisigUpdated {
+  pub multisig:  Pubkey,
+  pub authority: Pubkey,
+  pub signers:   Vec<Pubkey>,
+  pub threshold: u8,
+}
+
+#[event]
+pub struct MultisigProposalCreated {
+  pub multisig:   Pubkey,
+  pub proposal:   Pubkey,
+  pub index:      u64,
+  pub proposer:   Pubkey,
+  pub expires_at: i64,
+}
+
+#[event]
+pub struct MultisigProposalApproved {
+  pub proposal:       Pubkey,
+  pub signer:         Pubkey,
+  pub approval_count: u8,
+}
+
+#[event]
+pub struct MultisigProposalExecuted {
+  pub proposal: Pubkey,
+}
+
+#[event]
+pub struct MultisigProposalCancelled {
+  pub proposal: Pubkey,
+}
+
+// -- Multisig --
+
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateMultisig<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+
+  #[account(
+    init,
+    payer = payer,
+    space = 8 + Multisig::INIT_SPACE,
+    seeds = [Multisig::SEED_PREFIX, create_key.as_ref()],
+    bump
+  )]
+  pub multisig: Account<'info, Multisig>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn create_multisig(
+  ctx: Context<CreateMultisig>,
+  create_key: Pubkey,
+  signers: Vec<Pubkey>,
+  threshold: u8,
+) -> Result<()> {
+  require!(Multisig::is_valid(&signers, threshold), CctprError::InvalidMultisig);
+
+  ctx.accounts.multisig.set_inner(Multisig {
+    version: LayoutVersion,
+    create_key,
+    signers: signers.clone(),
+    threshold,
+    proposal_count: 0,
+    generation: 0,
+  });
+
+  let multisig = ctx.accounts.multisig.key();
+  emit!(MultisigUpdated { multisig, authority: Multisig::authority(&multisig).0, signers, threshold });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMultisig<'info> {
+  //i.e. only through one of the multisig's own proposals
+  #[account(seeds = [Multisig::AUTHORITY_SEED_PREFIX, multisig.key().as_ref()], bump)]
+  pub authority: Signer<'info>,
+
+  #[account(mut)]
+  pub multisig: Account<'info, Multisig>,
+}
+
+pub fn update_multisig(
+  ctx: Context<UpdateMultisig>,
+  signers: Vec<Pubkey>,
+  threshold: u8,
+) -> Result<()> {
+  require!(Multisig::is_valid(&signers, threshold), CctprError::InvalidMultisig);
+
+  let multisig = &mut ctx.accounts.multisig;
+  multisig.signers = signers.clone();
+  multisig.threshold = threshold;
+  //pending proposals were approved by (and counted against) the previous signers
+  multisig.generation += 1;
+  emit!(MultisigUpdated {
+    multisig: multisig.key(),
+    authority: ctx.accounts.authority.key(),
+    signers,
+    threshold,
+  });
+  Ok(())
+}
+
+// -- Proposals --
+
+#[derive(Accounts)]
+#[instruction(accounts: Vec<ProposedAccountMeta>, data: Vec<u8>)]
+pub struct ProposeMultisigInstruction<'info> {
+  //the proposer approves right away
+  #[account(mut, constraint = multisig.is_signer(proposer.key) @ CctprError::NotAuthorized)]
+  pub proposer: Signer<'info>,
+
+  #[account(mut)]
+  pub multisig: Account<'info, Multisig>,
+
+  #[account(
+    init,
+    payer = proposer,
+    space = MultisigProposal::space(accounts.len(), data.len()),
+    seeds = [
+      MultisigProposal::SEED_PREFIX,
+      multisig.key().as_ref(),
+      multisig.proposal_count.to_be_bytes().as_ref(),
+    ],
+    bump
+  )]
+  pub proposal: Account<'info, MultisigProposal>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn propose_multisig_instruction(
+  ctx: Context<ProposeMultisigInstruction>,
+  accounts: Vec<ProposedAccountMeta>,
+  data: Vec<u8>,
+) -> Result<()> {
+  let multisig = &mut ctx.accounts.multisig;
+  let index = multisig.proposal_count;
+  multisig.proposal_count += 1;
+
+  let proposer = ctx.accounts.proposer.key();
+  let expires_at = Clock::get()?.unix_timestamp + MultisigProposal::LIFETIME;
+  ctx.accounts.proposal.set_inner(MultisigProposal {
+    version: LayoutVersion,
+    multisig: multisig.key(),
+    index,
+    proposer,
+    generation: multisig.generation,
+    expires_at,
+    accounts,
+    data,
+    approvals: vec![proposer],
+  });
+
+  emit!(MultisigProposalCreated {
+    multisig: multisig.key(),
+    proposal: ctx.accounts.proposal.key(),
+    index,
+    proposer,
+    expires_at,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveMultisigProposal<'info> {
+  #[account(constraint = multisig.is_signer(signer.key) @ CctprError::NotAuthorized)]
+  pub signer: Signer<'info>,
+
+  pub multisig: Account<'info, Multisig>,
+
+  #[account(mut, has_one = multisig)]
+  pub proposal: Account<'info, MultisigProposal>,
+}
+
+pub fn approve_multisig_proposal(ctx: Context<ApproveMultisigProposal>) -> Result<()> {
+  let signer = ctx.accounts.signer.key();
+  let multisig = &ctx.accounts.multisig;
+  let proposal = &mut ctx.accounts.proposal;
+  proposal.check_pending(multisig, Clock::get()?.unix_timestamp)?;
+  require!(!proposal.approvals.contains(&signer), CctprError::AlreadyApproved);
+
+  //dropping the approvals of former signers keeps the approvals within the allocated space
+  proposal.approvals.retain(|approval| multisig.is_signer(approval));
+  proposal.approvals.push(signer);
+
+  emit!(MultisigProposalApproved {
+    proposal: proposal.key(),
+    signer,
+    approval_count: proposal.approval_count(multisig) as u8,
+  });
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigProposal<'info> {
+  pub multisig: Account<'info, Multisig>,
+
+  /// CHECK: seeds
+  #[account(mut, seeds = [Multisig::AUTHORITY_SEED_PREFIX, multisig.key().as_ref()], bump)]
+  pub authority: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    close = proposer,
+    has_one = multisig,
+    has_one = proposer,
+    constraint =
+      proposal.approval_count(&multisig) >= usize::from(multisig.threshold)
+      @ CctprError::NotEnoughApprovals,
+  )]
+  pub proposal: Account<'info, MultisigProposal>,
+
+  /// CHECK: gets the rent of the proposal back
+  #[account(mut)]
+  pub proposer: UncheckedAccount<'info>,
+
+  /// CHECK: this program, which the proposed instruction is invoked on
+  #[account(address = crate::ID)]
+  pub program: UncheckedAccount<'info>,
+}
+
+//permissionless once approved, the accounts of the proposed instruction are passed as remaining
+//  accounts
+pub fn execute_multisig_proposal<'info>(
+  ctx: Context<'_, '_, '_, 'info, ExecuteMultisigProposal<'info>>,
+) -> Result<()> {
+  ctx.accounts.proposal.check_pending(&ctx.accounts.multisig, Clock::get()?.unix_timestamp)?;
+
+  let mut account_infos = ctx.remaining_accounts.to_vec();
+  account_infos.push(ctx.accounts.authority.to_account_info());
+  account_infos.push(ctx.accounts.program.to_account_info());
+
+  let multisig = ctx.accounts.multisig.key();
+  invoke_signed(
+    &ctx.accounts.proposal.instruction(),
+    &account_infos,
+    &[&[Multisig::AUTHORITY_SEED_PREFIX, multisig.as_ref(), &[ctx.bumps.authority]]],
+  )?;
+
+  emit!(MultisigProposalExecuted { proposal: ctx.accounts.proposal.key() });
+  Ok(())
+}
+
+//by the multisig itself (i.e. through another proposal) or any of its signers, or by anyone once
+//  the proposal can't be executed anymore anyway
+#[derive(Accounts)]
+pub struct CancelMultisigProposal<'info> {
+  #[account(constraint =
+    multisig.is_signer(signer.key) ||
+    signer.key() == Multisig::authority(&multisig.key()).0 ||
+    proposal.check_pending(&multisig, Clock::get()?.unix_timestamp).is_err()
+    @ CctprError::NotAuthorized
+  )]
+  pub signer: Signer<'info>,
+
+  pub multisig: Account<'info, Multisig>,
+
+  #[account(mut, close = proposer, has_one = multisig, has_one = proposer)]
+  pub proposal: Account<'info, MultisigProposal>,
+
+  /// CHECK: gets the rent of the proposal back
+  #[account(mut)]
+  pub proposer: UncheckedAccount<'info>,
+}
+
+pub fn cancel_multisig_proposal(ctx: Context<CancelMultisigProposal>) -> Result<()> {
+  emit!(MultisigProposalCancelled { proposal: ctx.accounts.proposal.key() });
+  Ok(())
+}
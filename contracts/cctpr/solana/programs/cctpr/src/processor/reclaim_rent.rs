@@ -38,10 +38,10 @@ pub fn reclaim_rent(
       message_transmitter_config: ctx.accounts.message_transmitter_config.to_account_info(),
       message_sent_event_data: ctx.accounts.message_sent_event_data      .to_account_info(),
     },
-    &seeds,
+    seeds,
   );
 
-  if destination_message.len() == 0 {
+  if destination_message.is_empty() {
     let params = reclaim::v1::ReclaimEventAccountParams { attestation };
     reclaim::v1::reclaim_event_account(ctx, &params)
   } else {
@@ -80,7 +80,7 @@ pub fn transfer_surplus_sol(ctx: Context<TransferSurplusSol>) -> Result<()> {
         from: ctx.accounts.rent_custodian.to_account_info(),
         to:   ctx.accounts.fee_recipient .to_account_info(),
       },
-      &seeds,
+      seeds,
     ),
     ctx.accounts.rent_custodian.to_account_info().lamports(),
   )
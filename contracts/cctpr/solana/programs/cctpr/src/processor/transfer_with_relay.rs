@@ -1,42 +1,45 @@
+use anchor_lang::{prelude::*, system_program};
+use solana_keccak_hasher::hash;
+use solana_secp256k1_recover::secp256k1_recover;
+#[cfg(feature = "idl-build")]
 use anchor_lang::{
-  prelude::*,
-  system_program,
-  solana_program::{keccak::hash, secp256k1_recover::secp256k1_recover}
+  IdlBuild,
+  idl::types::{IdlSerialization, IdlType, IdlTypeDef, IdlTypeDefTy},
 };
 use anchor_spl::{
   token,
   token::{TokenAccount, Token}
 };
+use fee_math::{
+  corridor::{relay_fee_micro_usd, RelayFeeParams},
+  prices::ChainPrices,
+  FeeError,
+};
 use price_oracle::{
   int::Int,
-  utils::int_to_u64,
-  state::{PricesState, PriceOracleConfigState, TargetChainsConfig},
+  utils::{fee_result, int_to_u64, Platform},
+  state::{PricesState, PriceHistoryState, ZeroCopyPriceOracleConfigState, ZeroCopyPricesState},
 };
+#[cfg(feature = "idl-build")]
+use std::collections::BTreeMap;
+use std::io::{self, Read};
 use crate::{
   error::CctprError,
-  state::{Config, ChainConfig, FeeAdjustmentType},
+  state::{
+    Config, ZeroCopyConfig, ZeroCopyChainConfig, FeeAdjustmentType, FeeDiscount, Integrator,
+    TransferLimits, DenylistEntry, DenylistKind, BASIS_POINTS,
+  },
   cctp_cpi::deposit,
 };
 
-const DOMAIN_ID_SOLANA:     u8 = 5;
-const DOMAIN_ID_AVALANCHE:  u8 = 1;
+const DOMAIN_ID_SOLANA:    u32 = 5;
+const DOMAIN_ID_AVALANCHE: u32 = 1;
 const CHAIN_ID_AVALANCHE:  u16 = 6;
 
-//taken from EVM contract
-const AVAX_HOP_GAS_COST:              u32 = 281_200;
-
-const EVM_GAS_DROPOFF_GAS_COST:       u32 =  22_000;
-const EVM_V1_BILLED_SIZE:             u32 =     664;
-const EVM_V1_GAS_COST:                u32 = 165_000;
-const EVM_V2_BILLED_SIZE:             u32 =     793;
-const EVM_V2_GAS_COST:                u32 = 175_000;
-
-const SUI_GAS_DROPOFF_STORAGE_BYTES:  u32 =     260;
-const SUI_GAS_DROPOFF_COMPUTE_BUDGET: u32 =   1_000;
-const SUI_GAS_DROPOFF_STORAGE_REBATE: u32 =     260;
-const SUI_COMPUTE_BUDGET:             u32 =   2_000;
-const SUI_STORAGE_BYTES:              u32 =   2_363;
-const SUI_STORAGE_REBATE:             u32 =   1_979;
+//off-chain quotes and avax hop messages keep their original (unversioned) format with single byte
+//  domains as long as all domains fit into a u8, otherwise they are prefixed with this version and
+//  use 4 byte big endian domains (which also changes their length)
+const WIDE_DOMAIN_FORMAT_VERSION: u8 = 1;
 
 //TODO update
 #[cfg(feature = "mainnet")]
@@ -67,19 +70,36 @@ pub struct RelayRequest {
   pub gas_dropoff_micro_gas_token: u32,
 }
 
-#[event_cpi]
+#[event]
+pub struct IntegratorFeeSplit {
+  pub cctp_nonce: u64,
+  pub integrator: Pubkey,
+  pub charged_in_usdc: bool,
+  pub relay_fee: u64,
+  pub integrator_fee: u64,
+  pub protocol_fee: u64, //includes the gasless fee, if any
+}
+
 #[derive(Accounts)]
+#[instruction(input_amount: u64, mint_recipient: [u8; 32])]
 pub struct TransferWithRelay<'info> {
   #[account(mut)]
   pub payer: Signer<'info>,
 
   pub user: Signer<'info>, //only distinct from payer when gasless (or when using a delegate)
 
-  #[account(has_one = fee_recipient)]
-  pub config: Account<'info, Config>,
+  //read in place once migrated, outdated ones are upgraded on the fly (see AccountView)
+  #[account(
+    constraint = ZeroCopyConfig::view(config.as_ref())?.fee_recipient() == fee_recipient.key()
+      @ ErrorCode::ConstraintHasOne,
+  )]
+  pub config: AccountLoader<'info, ZeroCopyConfig>,
 
   // determines the destination chain
-  pub chain_config: Account<'info, ChainConfig>,
+  //not an AccountLoader since legacy chain configs have a different discriminator
+  /// CHECK: owner constraint, and ZeroCopyChainConfig::view checks the discriminator
+  #[account(owner = crate::ID)]
+  pub chain_config: UncheckedAccount<'info>,
 
   //we can't use the config as the rent recipient because the system program does not allow
   //  calling transfer on accounts with data, so we have to use a separate account for it
@@ -105,13 +125,18 @@ pub struct TransferWithRelay<'info> {
   #[account(mut)]
   pub user_usdc: Account<'info, TokenAccount>,
 
-  pub oracle_config: Account<'info, PriceOracleConfigState>,
+  //always allocated with room for a pending owner, so it can't be too short to be loaded
+  pub oracle_config: AccountLoader<'info, ZeroCopyPriceOracleConfigState>,
 
-  #[account(constraint = destination_prices.chain_id == chain_config.chain_id)]
-  pub destination_prices: Option<Account<'info, PricesState>>,
+  //read without deserializing them since they are needed for every on-chain quote
+  #[account(
+    constraint = destination_prices.load()?.chain_id() ==
+      ZeroCopyChainConfig::view(&chain_config)?.chain_id()
+  )]
+  pub destination_prices: Option<AccountLoader<'info, ZeroCopyPricesState>>,
 
-  #[account(constraint = avalanche_prices.chain_id == CHAIN_ID_AVALANCHE)]
-  pub avalanche_prices: Option<Account<'info, PricesState>>,
+  #[account(constraint = avalanche_prices.load()?.chain_id() == CHAIN_ID_AVALANCHE)]
+  pub avalanche_prices: Option<AccountLoader<'info, ZeroCopyPricesState>>,
 
   /// CHECK: PDA derived from the user's address and a unique 4 byte seed (e.g. the timestamp)
   #[account(mut)]
@@ -156,6 +181,74 @@ pub struct TransferWithRelay<'info> {
   pub token_program: Program<'info, Token>,
 
   pub system_program: Program<'info, System>,
+
+  //spelled out instead of using #[event_cpi], which would append them after the accounts below
+  /// CHECK: Only the event authority can invoke self-CPI
+  #[account(seeds = [b"__event_authority"], bump)]
+  pub event_authority: AccountInfo<'info>,
+
+  /// CHECK: Self-CPI will fail if the program is not the current program
+  pub program: AccountInfo<'info>,
+
+  //optional accounts that were added later go here, so that clients which predate them can keep
+  //  omitting them (see the allow-missing-optionals feature of anchor)
+
+  //receives a share of the relay fee if set (see IntegratorFeeSplit)
+  pub integrator: Option<Account<'info, Integrator>>,
+
+  /// CHECK: must be the integrator's address, only required if the fee is charged in SOL
+  #[account(mut)]
+  pub integrator_fee_recipient: Option<UncheckedAccount<'info>>,
+
+  //must be owned by the integrator's address, only required if the fee is charged in USDC
+  #[account(mut, token::mint = usdc_mint)]
+  pub integrator_usdc: Option<Account<'info, TokenAccount>>,
+
+  //must belong to either the user or the integrator, only affects on-chain quotes
+  pub fee_discount: Option<Account<'info, FeeDiscount>>,
+
+  //the denylist entries of the user and the mint recipient, both required iff a compliance role is
+  //  set and must not exist
+  //since they must not exist, they can only be checked against the canonical bump (any other bump
+  //  would yield a different, equally non-existent, address), so it can't be passed in instead
+  /// CHECK: see DenylistEntry
+  #[account(
+    seeds = [DenylistEntry::SEED_PREFIX, &[DenylistKind::Sender as u8], user.key().as_ref()],
+    bump
+  )]
+  pub sender_denylist_entry: Option<UncheckedAccount<'info>>,
+
+  /// CHECK: see DenylistEntry
+  #[account(
+    seeds = [
+      DenylistEntry::SEED_PREFIX,
+      &[DenylistKind::Recipient as u8],
+      mint_recipient.as_ref(),
+    ],
+    bump
+  )]
+  pub recipient_denylist_entry: Option<UncheckedAccount<'info>>,
+
+  //only required (alongside the respective prices) if the config enables price smoothing
+  #[account(constraint =
+    destination_price_history.chain_id == ZeroCopyChainConfig::view(&chain_config)?.chain_id()
+  )]
+  pub destination_price_history: Option<Box<Account<'info, PriceHistoryState>>>,
+
+  #[account(constraint = avalanche_price_history.chain_id == CHAIN_ID_AVALANCHE)]
+  pub avalanche_price_history: Option<Box<Account<'info, PriceHistoryState>>>,
+
+  //required once the config enables transfer limits, the chain is unlimited if it doesn't exist
+  /// CHECK: see TransferLimits
+  #[account(
+    mut,
+    seeds = [
+      TransferLimits::SEED_PREFIX,
+      ZeroCopyChainConfig::view(&chain_config)?.domain_id().to_be_bytes().as_ref()
+    ],
+    bump
+  )]
+  pub transfer_limits: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -175,6 +268,16 @@ impl From<Corridor> for FeeAdjustmentType {
   }
 }
 
+impl From<Corridor> for fee_math::corridor::Corridor {
+  fn from(corridor: Corridor) -> fee_math::corridor::Corridor {
+    match corridor {
+      Corridor::V1              => fee_math::corridor::Corridor::V1,
+      Corridor::V2Direct { .. } => fee_math::corridor::Corridor::V2Direct,
+      Corridor::AvaxHop  { .. } => fee_math::corridor::Corridor::AvaxHop,
+    }
+  }
+}
+
 impl From<Corridor> for u8 {
   fn from(corridor: Corridor) -> u8 {
     match corridor {
@@ -208,24 +311,112 @@ pub struct GaslessParams {
   expiration_time: u32,
 }
 
-#[derive(AnchorSerialize)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IntegratorParams {
+  pub fee_share_bps: u16,
+}
+
+//appended to the instruction arguments after the fact and hence treated as `None` if the
+//  instruction data ends before it, so that clients which predate integrators keep working
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct OptionalIntegratorParams(pub Option<IntegratorParams>);
+
+impl AnchorSerialize for OptionalIntegratorParams {
+  fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    self.0.serialize(writer)
+  }
+}
+
+impl AnchorDeserialize for OptionalIntegratorParams {
+  fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+    let mut tag = [0];
+    if reader.read(&mut tag)? == 0 {
+      return Ok(OptionalIntegratorParams(None));
+    }
+    Option::deserialize_reader(&mut tag.chain(reader)).map(OptionalIntegratorParams)
+  }
+}
+
+#[cfg(feature = "idl-build")]
+impl IdlBuild for OptionalIntegratorParams {
+  fn create_type() -> Option<IdlTypeDef> {
+    Some(IdlTypeDef {
+      name: Self::get_full_path(),
+      docs: vec!["Can be omitted entirely if it is the last instruction argument.".to_string()],
+      serialization: IdlSerialization::Borsh,
+      repr: None,
+      generics: vec![],
+      ty: IdlTypeDefTy::Type {
+        alias: IdlType::Option(Box::new(IdlType::Defined {
+          name: IntegratorParams::get_full_path(),
+          generics: vec![],
+        })),
+      },
+    })
+  }
+
+  fn insert_types(types: &mut BTreeMap<String, IdlTypeDef>) {
+    if let Some(ty) = IntegratorParams::create_type() {
+      types.insert(IntegratorParams::get_full_path(), ty);
+    }
+  }
+
+  fn get_full_path() -> String {
+    "OptionalIntegratorParams".to_string()
+  }
+}
+
 pub struct OffChainQuoteData {
-  source_domain:      u8,
-  destination_domain: u8,
+  source_domain:      u32,
+  destination_domain: u32,
   corridor:           u8,
-  gas_dropoff:     [u8; 4], //use array and .to_be_bytes() to avoid endianness issue
-  expiration_time: [u8; 4], //use array and .to_be_bytes() to avoid endianness issue
-  pay_in_usdc:      bool,
-  relay_fee:       [u8; 8], //use array and .to_be_bytes() to avoid endianness issue
+  gas_dropoff:        u32,
+  expiration_time:    u32,
+  pay_in_usdc:       bool,
+  relay_fee:          u64,
+}
+
+impl OffChainQuoteData {
+  pub fn encode(&self) -> Vec<u8> {
+    let mut data = Vec::with_capacity(27);
+    encode_domains(&mut data, &[self.source_domain, self.destination_domain]);
+    data.push(self.corridor);
+    data.extend_from_slice(&self.gas_dropoff.to_be_bytes());
+    data.extend_from_slice(&self.expiration_time.to_be_bytes());
+    data.push(self.pay_in_usdc.into());
+    data.extend_from_slice(&self.relay_fee.to_be_bytes());
+    data
+  }
 }
 
-#[derive(AnchorSerialize, InitSpace)]
 pub struct AvaxHopMessage {
-  pub destination_domain: u8,
+  pub destination_domain: u32,
   pub mint_recipient: [u8; 32],
-  pub gas_dropoff_micro_gas_token: [u8; 4], //use array and .to_be_bytes() to avoid endianness issue
+  pub gas_dropoff_micro_gas_token: u32,
+}
+
+impl AvaxHopMessage {
+  pub fn encode(&self) -> Vec<u8> {
+    let mut data = Vec::with_capacity(41);
+    encode_domains(&mut data, &[self.destination_domain]);
+    data.extend_from_slice(&self.mint_recipient);
+    data.extend_from_slice(&self.gas_dropoff_micro_gas_token.to_be_bytes());
+    data
+  }
+}
+
+//see WIDE_DOMAIN_FORMAT_VERSION
+fn encode_domains(data: &mut Vec<u8>, domains: &[u32]) {
+  if domains.iter().all(|&domain| domain <= u8::MAX.into()) {
+    data.extend(domains.iter().map(|&domain| domain as u8));
+  }
+  else {
+    data.push(WIDE_DOMAIN_FORMAT_VERSION);
+    data.extend(domains.iter().flat_map(|domain| domain.to_be_bytes()));
+  }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_with_relay(
   ctx: Context<TransferWithRelay>,
   input_amount: u64,
@@ -236,78 +427,77 @@ pub fn transfer_with_relay(
   gasless: Option<GaslessParams>,
   message_sent_event_data_seed: [u8; 4],
   message_sent_event_data_bump: u8,
+  OptionalIntegratorParams(integrator_params): OptionalIntegratorParams,
 ) -> Result<()> {
   let accs = &ctx.accounts;
+  let config = ZeroCopyConfig::view(accs.config.as_ref())?;
+  let chain_config = ZeroCopyChainConfig::view(&accs.chain_config)?;
+  let oracle_config = accs.oracle_config.load()?;
 
-  let destination_domain = accs.chain_config.domain_id;
+  let destination_domain = chain_config.domain_id();
   let is_avax_hop = matches!(corridor, Corridor::AvaxHop { .. });
 
-  let calc_onchain_relay_fee_usdc = || -> Result<u64> {
-    let corridor_fee_adjustment = accs.chain_config.get_fee_adjustment(corridor.into());
-
-    let avax_hop_execution_fee_micro_usd = conditional_fee(is_avax_hop, || {
-      calc_execution_fee_micro_usd(accs.avalanche_prices.as_deref(), AVAX_HOP_GAS_COST, 0, 0, 0, 0)
-    })?;
-
-    let destination_execution_fee_micro_usd = conditional_fee(
-      corridor_fee_adjustment.relative_percent_bps != 0,
-      || {
-        let (evm_transaction_gas, evm_transaction_size) =
-          if matches!(corridor, Corridor::V2Direct {..})
-            { (EVM_V2_GAS_COST, EVM_V2_BILLED_SIZE) }
-          else
-            { (EVM_V1_GAS_COST, EVM_V1_BILLED_SIZE) };
-
-        let (evm_transaction_gas, sui_computation_units, sui_stored_bytes, sui_deleted_bytes) =
-          if gas_dropoff_micro_gas_token == 0 {
-            ( evm_transaction_gas,
-              SUI_COMPUTE_BUDGET,
-              SUI_STORAGE_BYTES,
-              SUI_STORAGE_REBATE,
-            )
-          }
-          else {
-            ( evm_transaction_gas + EVM_GAS_DROPOFF_GAS_COST,
-              SUI_COMPUTE_BUDGET + SUI_GAS_DROPOFF_COMPUTE_BUDGET,
-              SUI_STORAGE_BYTES  + SUI_GAS_DROPOFF_STORAGE_BYTES,
-              SUI_STORAGE_REBATE + SUI_GAS_DROPOFF_STORAGE_REBATE,
-            )
-          };
-
-        calc_execution_fee_micro_usd(
-          accs.destination_prices.as_deref(),
-          evm_transaction_gas,
-          evm_transaction_size,
-          sui_computation_units,
-          sui_stored_bytes,
-          sui_deleted_bytes
-        )
-      }
-    )?;
-
-    let total_execution_fee_micro_usd = corridor_fee_adjustment.apply(
-      int_to_u64(Int::Ok(avax_hop_execution_fee_micro_usd) + destination_execution_fee_micro_usd)?
-    )?;
+  check_mint_recipient(chain_config.chain_id(), &mint_recipient)?;
 
-    let gas_dropoff_fee_micro_usd = conditional_fee(gas_dropoff_micro_gas_token > 0, || {
-      let gas_dropoff_fee_adjustment =
-        accs.chain_config.get_fee_adjustment(FeeAdjustmentType::GasDropoff);
+  if config.compliance() != Pubkey::default() {
+    check_not_denylisted(accs.sender_denylist_entry.as_deref())?;
+    check_not_denylisted(accs.recipient_denylist_entry.as_deref())?;
+  }
 
-      let unadjusted_micro_usd = conditional_fee(
-        gas_dropoff_fee_adjustment.relative_percent_bps != 0,
-        || {
-          require!(accs.destination_prices.is_some(), CctprError::InvalidTransferArgs);
-          accs.destination_prices.as_ref().unwrap()
-            .micro_gas_token_to_micro_usd(gas_dropoff_micro_gas_token)
-        }
-      )?;
+  let mut fee_discount = accs.fee_discount.as_deref();
+  if let Some(discount) = fee_discount {
+    require!(
+      discount.subject == accs.user.key() ||
+        Some(discount.subject) == accs.integrator.as_ref().map(|integrator| integrator.address),
+      CctprError::InvalidTransferArgs
+    );
+    //an expired discount is ignored so that clients which still pass it can keep transferring
+    let now = Clock::get()?.unix_timestamp as u32;
+    if now >= discount.expiration_time {
+      fee_discount = None;
+    }
+  }
+  //applied on top of the chain config's fee adjustment
+  let fee_discount_adjustment = |adjustment_type: FeeAdjustmentType| {
+    fee_discount.map(|discount| discount.get_fee_adjustment(adjustment_type).into())
+  };
 
-      gas_dropoff_fee_adjustment.apply(unadjusted_micro_usd)
-    })?;
+  let calc_onchain_relay_fee_usdc = || -> Result<u64> {
+    let smoothing_window = config.price_smoothing_window();
+    let avalanche_prices = quoting_prices(
+      accs.avalanche_prices.as_ref(),
+      accs.avalanche_price_history.as_deref().map(|history| &**history),
+      smoothing_window,
+    )?;
+    let destination_prices = quoting_prices(
+      accs.destination_prices.as_ref(),
+      accs.destination_price_history.as_deref().map(|history| &**history),
+      smoothing_window,
+    )?;
 
-    int_to_u64(Int::Ok(total_execution_fee_micro_usd) + gas_dropoff_fee_micro_usd)
+    let relay_fee_micro_usd = relay_fee_micro_usd(&RelayFeeParams {
+      corridor:                    corridor.into(),
+      gas_dropoff_micro_gas_token,
+      avalanche_prices:            avalanche_prices.as_ref(),
+      destination_prices:          destination_prices.as_ref(),
+      corridor_adjustment:         chain_config.get_fee_adjustment(corridor.into()).into(),
+      gas_dropoff_adjustment:
+        chain_config.get_fee_adjustment(FeeAdjustmentType::GasDropoff).into(),
+      corridor_discount:           fee_discount_adjustment(corridor.into()),
+      gas_dropoff_discount:        fee_discount_adjustment(FeeAdjustmentType::GasDropoff),
+    });
+    match relay_fee_micro_usd {
+      Err(FeeError::MissingPrices) => Err(CctprError::InvalidTransferArgs.into()),
+      relay_fee_micro_usd => fee_result(relay_fee_micro_usd),
+    }
   };
 
+  let avax_hop_message = is_avax_hop.then(|| AvaxHopMessage {
+    destination_domain,
+    mint_recipient,
+    gas_dropoff_micro_gas_token,
+  }.encode());
+
   let rent_rebate_sol =
     Rent::get()?.minimum_balance(
       if corridor == Corridor::V1 {
@@ -315,7 +505,7 @@ pub fn transfer_with_relay(
       }
       else {
         deposit::v2::MESSAGE_SENT_EVENT_DATA_SIZE +
-          if is_avax_hop { AvaxHopMessage::INIT_SPACE } else { 0 }
+          avax_hop_message.as_ref().map_or(0, Vec::len)
       }
     );
 
@@ -334,13 +524,13 @@ pub fn transfer_with_relay(
     RelayQuote::OnChainGas  { max_relay_fee_sol } => {
       let relay_fee_usdc = calc_onchain_relay_fee_usdc()?;
       let relay_fee_sol =
-        accs.oracle_config.micro_usd_to_sol(relay_fee_usdc)?.saturating_sub(rent_rebate_sol);
+        oracle_config.micro_usd_to_sol(relay_fee_usdc)?.saturating_sub(rent_rebate_sol);
       require!(relay_fee_sol <= max_relay_fee_sol, CctprError::ExceedsMaxFee);
       (false, relay_fee_sol, input_amount)
     }
     RelayQuote::OnChainUsdc { max_relay_fee_usdc, take_fee_from_input } => {
       let rent_rebate_usdc = conditional_fee(gasless.is_none(), || {
-        accs.oracle_config.sol_to_micro_usd(rent_rebate_sol)
+        oracle_config.sol_to_micro_usd(rent_rebate_sol)
       })?;
       let relay_fee_usdc = calc_onchain_relay_fee_usdc()?.saturating_sub(rent_rebate_usdc);
       require!(relay_fee_usdc <= max_relay_fee_usdc, CctprError::ExceedsMaxFee);
@@ -366,16 +556,16 @@ pub fn transfer_with_relay(
         source_domain:   DOMAIN_ID_SOLANA,
         destination_domain,
         corridor:        corridor.into(),
-        gas_dropoff:     gas_dropoff_micro_gas_token.to_be_bytes(),
-        expiration_time: expiration_time.to_be_bytes(),
+        gas_dropoff:     gas_dropoff_micro_gas_token,
+        expiration_time,
         pay_in_usdc:     charge_in_usdc,
-        relay_fee:       relay_fee.to_be_bytes(),
+        relay_fee,
       };
-      let quote_hash = hash(&quote_data.try_to_vec().unwrap().as_slice()).0;
+      let quote_hash = hash(quote_data.encode().as_slice()).0;
       require!(
         secp256k1_recover(&quote_hash, quoter_signature[64] - 27, &quoter_signature[..64])
           .map(|pubkey| hash(&pubkey.0).0)
-          .ok().filter(|recovered_pubkey| &recovered_pubkey[12..] == accs.config.offchain_quoter)
+          .ok().filter(|recovered_pubkey| recovered_pubkey[12..] == config.offchain_quoter())
           .is_some(),
         CctprError::OffchainQuoterSignatureInvalid,
       );
@@ -397,30 +587,59 @@ pub fn transfer_with_relay(
     relay_fee
   };
 
-  if charge_in_usdc {
-    token::transfer(
-      CpiContext::new(
-        accs.token_program                   .to_account_info(),
-        token::Transfer {
-          from:      accs.user_usdc          .to_account_info(),
-          to:        accs.fee_recipient_usdc .to_account_info(),
-          authority: accs.user               .to_account_info(),
-        },
-      ),
-      total_fee,
-    )?;
+  if config.transfer_limits_enabled() {
+    let transfer_limits =
+      accs.transfer_limits.as_ref().ok_or(CctprError::InvalidTransferArgs)?;
+    //its derivation ensures that only we could have created it
+    if !transfer_limits.data_is_empty() {
+      let mut data = transfer_limits.try_borrow_mut_data()?;
+      let mut limits = TransferLimits::try_deserialize(&mut &data[..])?;
+      limits.consume(transfer_amount, Clock::get()?.unix_timestamp as u32)?;
+      limits.try_serialize(&mut &mut data[..])?;
+    }
+  }
+
+  let integrator_fee = if let Some(integrator) = &accs.integrator {
+    let integrator_fee_share_bps = integrator_params.map_or(0, |params| params.fee_share_bps);
+    require!(
+      integrator_fee_share_bps <= integrator.max_fee_share_bps,
+      CctprError::ExceedsMaxIntegratorFeeShare
+    );
+    int_to_u64(Int::Ok(relay_fee) * u64::from(integrator_fee_share_bps) / BASIS_POINTS)?
   }
   else {
-    system_program::transfer(
-      CpiContext::new(
-        accs.system_program        .to_account_info(),
-        system_program::Transfer {
-          from: accs.payer         .to_account_info(),
-          to:   accs.fee_recipient .to_account_info(),
-        },
-      ),
-      total_fee,
-    )?;
+    require!(integrator_params.is_none(), CctprError::InvalidTransferArgs);
+    0
+  };
+  let protocol_fee = total_fee - integrator_fee;
+
+  let fee_recipient = if charge_in_usdc {
+    accs.fee_recipient_usdc.to_account_info()
+  }
+  else {
+    accs.fee_recipient.to_account_info()
+  };
+  transfer_fee(accs, charge_in_usdc, fee_recipient, protocol_fee)?;
+
+  if integrator_fee > 0 {
+    let integrator_address = accs.integrator.as_ref().unwrap().address;
+    let integrator_fee_recipient = if charge_in_usdc {
+      let integrator_usdc =
+        accs.integrator_usdc.as_ref().ok_or(CctprError::InvalidTransferArgs)?;
+      require_keys_eq!(integrator_usdc.owner, integrator_address, CctprError::InvalidTransferArgs);
+      integrator_usdc.to_account_info()
+    }
+    else {
+      let integrator_fee_recipient =
+        accs.integrator_fee_recipient.as_ref().ok_or(CctprError::InvalidTransferArgs)?;
+      require_keys_eq!(
+        integrator_fee_recipient.key(),
+        integrator_address,
+        CctprError::InvalidTransferArgs
+      );
+      integrator_fee_recipient.to_account_info()
+    };
+    transfer_fee(accs, charge_in_usdc, integrator_fee_recipient, integrator_fee)?;
   }
 
   let user_key = accs.user.key();
@@ -429,7 +648,7 @@ pub fn transfer_with_relay(
     message_sent_event_data_seed.as_ref(),
     &[message_sent_event_data_bump],
   ];
-  let rent_seeds: &[&[u8]] = &[Config::RENT_SEED_PREFIX, &[ctx.accounts.config.rent_bump]];
+  let rent_seeds: &[&[u8]] = &[Config::RENT_SEED_PREFIX, &[config.rent_bump()]];
   let signer_seeds: &[&[&[u8]]] = &[message_sent_event_data_seeds, rent_seeds];
 
   let cctp_nonce =
@@ -475,7 +694,7 @@ pub fn transfer_with_relay(
         ),
         deposit::v1::DepositForBurnParams {
           amount: transfer_amount,
-          destination_domain: destination_domain as u32,
+          destination_domain,
           mint_recipient,
         },
       )?
@@ -533,7 +752,7 @@ pub fn transfer_with_relay(
       let burn_params = deposit::v2::DepositForBurnParams {
         amount: transfer_amount,
         destination_domain:
-          if is_avax_hop { DOMAIN_ID_AVALANCHE } else { destination_domain } as u32,
+          if is_avax_hop { DOMAIN_ID_AVALANCHE } else { destination_domain },
         mint_recipient,
         destination_caller:
           if is_avax_hop { AVALANCHE_ROUTER_ADDRESS } else { [0; 32] },
@@ -541,18 +760,12 @@ pub fn transfer_with_relay(
         min_finality_threshold: 0,
       };
 
-      if is_avax_hop {
-        let avax_hop_message = AvaxHopMessage {
-          destination_domain,
-          mint_recipient,
-          gas_dropoff_micro_gas_token: gas_dropoff_micro_gas_token.to_be_bytes(),
-        };
-
+      if let Some(avax_hop_message) = avax_hop_message {
         deposit::v2::deposit_for_burn_with_hook(
           burn_ctx,
           deposit::v2::DepositForBurnWithHookParams {
             shared: burn_params,
-            hook_data: avax_hop_message.try_to_vec().unwrap(),
+            hook_data: avax_hop_message,
           },
         )?;
       }
@@ -565,26 +778,91 @@ pub fn transfer_with_relay(
 
   emit_cpi!(RelayRequest{ cctp_nonce, gas_dropoff_micro_gas_token });
 
+  if let Some(integrator) = &ctx.accounts.integrator {
+    emit_cpi!(IntegratorFeeSplit {
+      cctp_nonce,
+      integrator: integrator.address,
+      charged_in_usdc: charge_in_usdc,
+      relay_fee,
+      integrator_fee,
+      protocol_fee,
+    });
+  }
+
   Ok(())
 }
 
-fn calc_execution_fee_micro_usd(
-  prices: Option<&PricesState>,
-  evm_transaction_gas: u32,
-  evm_transaction_size: u32,
-  sui_computation_units: u32,
-  sui_stored_bytes: u32,
-  sui_deleted_bytes: u32,
-) -> Result<u64> {
-  require!(prices.is_some(), CctprError::InvalidTransferArgs);
-  let config = TargetChainsConfig {
-    evm_transaction_gas,
-    evm_transaction_size,
-    sui_computation_units,
-    sui_stored_bytes,
-    sui_deleted_bytes,
+//recipient is a USDC token account if the fee is charged in USDC and a wallet otherwise
+fn transfer_fee<'info>(
+  accs: &TransferWithRelay<'info>,
+  charge_in_usdc: bool,
+  recipient: AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  if charge_in_usdc {
+    token::transfer(
+      CpiContext::new(
+        accs.token_program          .to_account_info(),
+        token::Transfer {
+          from:      accs.user_usdc .to_account_info(),
+          to:        recipient,
+          authority: accs.user      .to_account_info(),
+        },
+      ),
+      amount,
+    )
+  }
+  else {
+    system_program::transfer(
+      CpiContext::new(
+        accs.system_program .to_account_info(),
+        system_program::Transfer {
+          from: accs.payer  .to_account_info(),
+          to:   recipient,
+        },
+      ),
+      amount,
+    )
+  }
+}
+
+//CCTP accepts any 32 bytes, but e.g. an EVM address with dirty upper bytes or an all zero
+//  recipient would render the funds irrecoverable
+fn check_mint_recipient(chain_id: u16, mint_recipient: &[u8; 32]) -> Result<()> {
+  let is_valid = *mint_recipient != [0; 32] && match Platform::from_chain_id(chain_id) {
+    Some(Platform::Evm) => mint_recipient[..12] == [0; 12],
+    Some(Platform::Sui) | Some(Platform::Sol) => true,
+    None => false,
+  };
+  require!(is_valid, CctprError::InvalidMintRecipient);
+  Ok(())
+}
+
+//the entry's derivation is checked by its seeds constraint
+fn check_not_denylisted(entry: Option<&AccountInfo>) -> Result<()> {
+  let entry = entry.ok_or(CctprError::InvalidTransferArgs)?;
+  require!(!DenylistEntry::is_denylisted(Some(entry)), CctprError::Denylisted);
+  Ok(())
+}
+
+//the spot prices, or their time-weighted average if price smoothing is enabled
+fn quoting_prices(
+  prices: Option<&AccountLoader<ZeroCopyPricesState>>,
+  history: Option<&PriceHistoryState>,
+  smoothing_window: u32,
+) -> Result<Option<ChainPrices>> {
+  let Some(prices) = prices else {
+    return Ok(None);
   };
-  prices.unwrap().calc_total_fee_micro_usd(&config, 0, 0)
+  let prices: PricesState = prices.load()?.prices_state()?;
+  if smoothing_window == 0 {
+    return Ok(prices.chain_prices());
+  }
+
+  let history = history.ok_or(CctprError::InvalidTransferArgs)?;
+  let now = Clock::get()?.unix_timestamp;
+  let smoothed = history.twap(now, smoothing_window).ok_or(CctprError::InvalidTransferArgs)?;
+  Ok(prices.with_observation(&smoothed).chain_prices())
 }
 
 // makes code more DRY by replacing `if condition { computation()? } else { 0 }`
@@ -593,3 +871,46 @@ fn conditional_fee<F>(condition: bool, computation: F) -> Result<u64>
 {
   condition.then(computation).transpose().map(|opt| opt.unwrap_or(0))
 }
+
+#[test]
+fn mint_recipient_is_checked_against_destination_platform() {
+  use price_oracle::utils::{ETHEREUM_CHAIN_ID, SUI_CHAIN_ID};
+
+  let mut evm_address = [0; 32];
+  evm_address[12..].copy_from_slice(&[0xab; 20]);
+  assert!(check_mint_recipient(ETHEREUM_CHAIN_ID, &evm_address).is_ok());
+  assert!(check_mint_recipient(CHAIN_ID_AVALANCHE, &evm_address).is_ok());
+  assert!(check_mint_recipient(SUI_CHAIN_ID, &evm_address).is_ok());
+
+  // Dirty upper bytes are fine for Sui addresses but not for EVM ones:
+  assert!(check_mint_recipient(SUI_CHAIN_ID, &[0xab; 32]).is_ok());
+  assert_eq!(
+    check_mint_recipient(ETHEREUM_CHAIN_ID, &[0xab; 32]).unwrap_err(),
+    CctprError::InvalidMintRecipient.into(),
+  );
+
+  for chain_id in [ETHEREUM_CHAIN_ID, SUI_CHAIN_ID] {
+    assert_eq!(
+      check_mint_recipient(chain_id, &[0; 32]).unwrap_err(),
+      CctprError::InvalidMintRecipient.into(),
+    );
+  }
+}
+
+#[test]
+fn integrator_params_can_be_omitted() {
+  let params = OptionalIntegratorParams(Some(IntegratorParams { fee_share_bps: 1_000 }));
+  let data = params.try_to_vec().unwrap();
+  assert_eq!(OptionalIntegratorParams::try_from_slice(&data).unwrap(), params);
+  assert_eq!(
+    OptionalIntegratorParams::try_from_slice(&[0]).unwrap(),
+    OptionalIntegratorParams(None),
+  );
+
+  // Instruction data of clients that predate integrators simply ends before it:
+  assert_eq!(
+    OptionalIntegratorParams::try_from_slice(&[]).unwrap(),
+    OptionalIntegratorParams(None),
+  );
+  assert!(OptionalIntegratorParams::try_from_slice(&[2]).is_err());
+}
@@ -1,23 +1,191 @@
-use anchor_lang::prelude::*;
-use price_oracle::{int::Int, utils::int_to_u64};
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, ZeroCopy};
+#[cfg(feature = "idl-build")]
+use anchor_lang::{
+  IdlBuild,
+  idl::types::{
+    IdlDefinedFields, IdlSerialization, IdlType, IdlTypeDef, IdlTypeDefGeneric, IdlTypeDefTy,
+  },
+};
+use bytemuck::{Pod, Zeroable};
+use std::{cell::Ref, io, ops::Deref};
+use price_oracle::{state::{MultisigState, StaleProposal}, utils::int_to_u64};
+use crate::{error::CctprError, migration};
+
+/// The layout version of an account, stored right after its discriminator.
+///
+/// Deserialization fails for any other version, so outdated accounts can't be used until they
+/// have been upgraded in place via the `migrate` instruction (see `migration`).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LayoutVersion<const V: u8>;
+
+impl<const V: u8> LayoutVersion<V> {
+  pub const VALUE: u8 = V;
+}
+
+impl<const V: u8> Space for LayoutVersion<V> {
+  const INIT_SPACE: usize = 1;
+}
+
+impl<const V: u8> AnchorSerialize for LayoutVersion<V> {
+  fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    V.serialize(writer)
+  }
+}
+
+impl<const V: u8> AnchorDeserialize for LayoutVersion<V> {
+  fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+    match u8::deserialize_reader(reader)? {
+      //anchor deserializes freshly allocated (i.e. zeroed) accounts on `init`
+      0 => Ok(LayoutVersion),
+      version if version == V => Ok(LayoutVersion),
+      _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Outdated account layout")),
+    }
+  }
+}
+
+#[cfg(feature = "idl-build")]
+impl<const V: u8> IdlBuild for LayoutVersion<V> {
+  fn create_type() -> Option<IdlTypeDef> {
+    Some(IdlTypeDef {
+      name: "LayoutVersion".to_string(),
+      docs: vec![],
+      serialization: IdlSerialization::Borsh,
+      repr: None,
+      generics: vec![IdlTypeDefGeneric::Const { name: "V".to_string(), ty: "u8".to_string() }],
+      ty: IdlTypeDefTy::Struct { fields: Some(IdlDefinedFields::Tuple(vec![IdlType::U8])) },
+    })
+  }
+
+  fn get_full_path() -> String {
+    "LayoutVersion".to_string()
+  }
+}
 
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
+  pub version:         LayoutVersion<{ Config::VERSION }>,
   pub owner:           Pubkey,
   pub pending_owner:   Pubkey,
   pub fee_adjuster:    Pubkey,
   pub fee_recipient:   Pubkey,
   pub offchain_quoter: [u8; 20],
   pub rent_bump:       u8,
+  /// Manages the denylist, which is only enforced if set.
+  pub compliance:      Pubkey,
+  /// If non-zero, on-chain quotes use the time-weighted average prices over this many seconds
+  /// (see `PriceHistoryState::twap`) instead of the spot prices.
+  pub price_smoothing_window: u32,
+  /// Delays sensitive governance actions, see `TimelockedAction`.
+  pub timelock:        Timelock,
+  pub instant_change_allowance: InstantChangeAllowance,
+  /// Set once the first transfer limits are configured, from then on transfers have to pass the
+  /// `TransferLimits` account of their chain (which is unlimited as long as it doesn't exist).
+  pub transfer_limits_enabled: bool,
 }
 
 impl Config {
+  pub const VERSION: u8 = 1;
   pub const SEED_PREFIX: &[u8] = b"config";
   pub const RENT_SEED_PREFIX: &[u8] = b"rent";
+
+  //the fee adjuster can propose fee adjustment changes, just like it can make small ones directly
+  pub fn may_propose(&self, proposer: &Pubkey, action: &TimelockedAction) -> bool {
+    *proposer == self.owner || (
+      *proposer == self.fee_adjuster &&
+      matches!(action, TimelockedAction::UpdateFeeAdjustment { .. })
+    )
+  }
+}
+
+/// The account layout of a (migrated) `Config`, so that transfers can read it without
+/// deserializing it, i.e. via `AccountLoader<'info, ZeroCopyConfig>`.
+//all fields are byte arrays so there's no padding and the layout matches the borsh one, the ones
+//  that only governance uses (which deserializes the config) are left opaque
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ZeroCopyConfig {
+  version:                 u8,
+  _roles:                  [u8; 3 * 32], //owner, pending owner and fee adjuster
+  fee_recipient:           Pubkey,
+  offchain_quoter:         [u8; 20],
+  rent_bump:               u8,
+  compliance:              Pubkey,
+  price_smoothing_window:  [u8; 4],
+  _timelock:               [u8; Timelock::INIT_SPACE + InstantChangeAllowance::INIT_SPACE],
+  transfer_limits_enabled: u8,
+}
+
+impl ZeroCopyConfig {
+  //configs that were created before the layout was versioned are shorter until they have been
+  //  migrated, until then they are upgraded like the migration would
+  pub fn view<'a>(config: &'a AccountInfo) -> Result<AccountView<'a, Self>> {
+    view_account(
+      config,
+      |config: &Self| config.version == Config::VERSION,
+      |data| Ok(Self::from(&migration::load_config(data)?.0)),
+    )
+  }
+
+  pub fn fee_recipient(&self) -> Pubkey {
+    self.fee_recipient
+  }
+
+  pub fn offchain_quoter(&self) -> [u8; 20] {
+    self.offchain_quoter
+  }
+
+  pub fn rent_bump(&self) -> u8 {
+    self.rent_bump
+  }
+
+  pub fn compliance(&self) -> Pubkey {
+    self.compliance
+  }
+
+  pub fn price_smoothing_window(&self) -> u32 {
+    u32::from_le_bytes(self.price_smoothing_window)
+  }
+
+  pub fn transfer_limits_enabled(&self) -> bool {
+    self.transfer_limits_enabled != 0
+  }
+}
+
+impl ZeroCopy for ZeroCopyConfig {}
+
+impl Owner for ZeroCopyConfig {
+  fn owner() -> Pubkey {
+    crate::ID
+  }
+}
+
+impl Discriminator for ZeroCopyConfig {
+  const DISCRIMINATOR: &'static [u8] = Config::DISCRIMINATOR;
+}
+
+//the layouts match, see above
+impl From<&Config> for ZeroCopyConfig {
+  fn from(config: &Config) -> Self {
+    let mut data = Vec::with_capacity(Config::INIT_SPACE);
+    config.serialize(&mut data).expect("writing to a vec can't fail");
+    bytemuck::pod_read_unaligned(&data)
+  }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+//clients see the same account as when it's deserialized
+#[cfg(feature = "idl-build")]
+impl IdlBuild for ZeroCopyConfig {
+  fn create_type() -> Option<IdlTypeDef> {
+    Config::create_type()
+  }
+
+  fn insert_types(types: &mut std::collections::BTreeMap<String, IdlTypeDef>) {
+    Config::insert_types(types)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FeeAdjustmentType {
   V1,
@@ -30,50 +198,985 @@ impl FeeAdjustmentType {
   pub const COUNT: usize = 4;
 }
 
-#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Default, Clone)]
+//#[max_len] only accepts plain identifiers
+const FEE_ADJUSTMENT_TYPE_COUNT: usize = FeeAdjustmentType::COUNT;
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Default, Clone, PartialEq, Eq, Debug)]
 pub struct FeeAdjustment {
   pub absolute_usd: i32,
   pub relative_percent_bps: u32,
 }
 
+pub use fee_math::adjustment::BASIS_POINTS;
+
 impl FeeAdjustment {
-  const BASIS_POINTS: u64 = 10_000;
+  const NONE: FeeAdjustment = FeeAdjustment { absolute_usd: 0, relative_percent_bps: 0 };
+  const IDENTITY: FeeAdjustment =
+    FeeAdjustment { absolute_usd: 0, relative_percent_bps: BASIS_POINTS as u32 };
+
   pub fn apply(&self, micro_usd: u64) -> Result<u64> {
-    let with_relative = Int::Ok(micro_usd) * self.relative_percent_bps as u64 / Self::BASIS_POINTS;
-    let with_absolute = if self.absolute_usd >= 0 {
-      int_to_u64(with_relative + self.absolute_usd as u64)?
-    } else {
-      let with_relative_u64 = int_to_u64(with_relative)?;
-      if with_relative_u64 > (-self.absolute_usd) as u64 {
-        with_relative_u64 - (-self.absolute_usd) as u64
-      } else {
-        0
+    int_to_u64(fee_math::adjustment::FeeAdjustment::from(self).apply(micro_usd))
+  }
+}
+
+impl From<&FeeAdjustment> for fee_math::adjustment::FeeAdjustment {
+  fn from(adjustment: &FeeAdjustment) -> Self {
+    fee_math::adjustment::FeeAdjustment {
+      absolute_usd:         adjustment.absolute_usd,
+      relative_percent_bps: adjustment.relative_percent_bps,
+    }
+  }
+}
+
+impl From<FeeAdjustment> for fee_math::adjustment::FeeAdjustment {
+  fn from(adjustment: FeeAdjustment) -> Self {
+    Self::from(&adjustment)
+  }
+}
+
+#[test]
+fn fee_adjustment_rounds_up() {
+  let adjustment = FeeAdjustment { absolute_usd: 0, relative_percent_bps: 15_000 };
+  assert_eq!(adjustment.apply(1).unwrap(), 2);
+  assert_eq!(adjustment.apply(2).unwrap(), 3);
+  assert_eq!(FeeAdjustment::IDENTITY.apply(7).unwrap(), 7);
+
+  let discount = FeeAdjustment { absolute_usd: -1, relative_percent_bps: 3_333 };
+  assert_eq!(discount.apply(10).unwrap(), 3);
+}
+
+#[cfg(test)]
+mod fee_adjustment_proptests {
+  use super::*;
+  use proptest::prelude::*;
+
+  proptest! {
+    #[test]
+    fn negative_fee_adjustments_are_monotonic_and_bounded(
+      absolute_usd in i32::MIN..=0,
+      relative_percent_bps: u32,
+      a: u64,
+      b: u64,
+    ) {
+      let adjustment = FeeAdjustment { absolute_usd, relative_percent_bps };
+      let (lower, upper) = (a.min(b), a.max(b));
+      if let (Ok(lower_fee), Ok(upper_fee)) = (adjustment.apply(lower), adjustment.apply(upper)) {
+        prop_assert!(lower_fee <= upper_fee);
+
+        let relative_only = FeeAdjustment { absolute_usd: 0, relative_percent_bps }.apply(upper);
+        let relative_only = relative_only.unwrap();
+        prop_assert!(upper_fee <= relative_only);
+        prop_assert!(upper_fee >= relative_only.saturating_sub(absolute_usd.unsigned_abs().into()));
       }
-    };
-    Ok(with_absolute)
+    }
   }
 }
 
-#[account]
+// Equals sha256("account:ChainConfigV2")[..8], since the default one is still used by the
+//   legacy u8-domain accounts (see LegacyChainConfig)
+#[account(discriminator = [0x3f, 0x80, 0x08, 0xf7, 0x27, 0xf9, 0xf9, 0xcc])]
 #[derive(InitSpace)]
 pub struct ChainConfig {
+  pub version: LayoutVersion<{ ChainConfig::VERSION }>,
+  pub domain_id: u32,
+  pub chain_id: u16,
+  //a vec rather than an array so that adding a new FeeAdjustmentType only requires growing
+  //  existing accounts (via `migrate`) rather than a new layout
+  #[max_len(FEE_ADJUSTMENT_TYPE_COUNT)]
+  fee_adjustments: Vec<FeeAdjustment>,
+}
+
+impl ChainConfig {
+  pub const VERSION: u8 = 1;
+  //the domain id is used as a 4 byte big endian seed, so the addresses don't collide with the
+  //  legacy ones (which used a single byte)
+  pub const SEED_PREFIX: &[u8] = b"chain_config";
+
+  pub fn new(domain_id: u32, chain_id: u16) -> Self {
+    ChainConfig {
+      version: LayoutVersion,
+      domain_id,
+      chain_id,
+      fee_adjustments: vec![FeeAdjustment::default(); FeeAdjustmentType::COUNT],
+    }
+  }
+
+  //adjustment types that were added after the account was created (and before it was migrated)
+  //  are treated as if they weren't set
+  pub fn get_fee_adjustment(&self, adjustment_type: FeeAdjustmentType) -> &FeeAdjustment {
+    self.fee_adjustments.get(adjustment_type as usize).unwrap_or(&FeeAdjustment::NONE)
+  }
+
+  pub fn set_fee_adjustment(
+    &mut self,
+    adjustment_type: FeeAdjustmentType,
+    adjustment: FeeAdjustment
+  ) -> Result<()> {
+    let fee_adjustment = self.fee_adjustments
+      .get_mut(adjustment_type as usize)
+      .ok_or(CctprError::AccountNotMigrated)?;
+    *fee_adjustment = adjustment;
+    Ok(())
+  }
+
+  pub fn fee_adjustment_count(&self) -> usize {
+    self.fee_adjustments.len()
+  }
+
+  pub(crate) fn pad_fee_adjustments(&mut self) {
+    self.fee_adjustments.resize(FeeAdjustmentType::COUNT, FeeAdjustment::default());
+  }
+}
+
+/// The original chain config which stored CCTP domains as a `u8`.
+///
+/// Existing accounts can still be used for transfers and configured (see `AnyChainConfig`) but have
+/// to be migrated to a `ChainConfig` (via `migrate_chain_config`) before they can be updated.
+// Equals sha256("account:ChainConfig")[..8]
+#[account(discriminator = [0x0d, 0xb1, 0xe9, 0x8d, 0xd4, 0x1d, 0x94, 0x38])]
+#[derive(InitSpace)]
+pub struct LegacyChainConfig {
   pub domain_id: u8,
   pub chain_id: u16,
   fee_adjustments: [FeeAdjustment; FeeAdjustmentType::COUNT],
 }
 
-impl ChainConfig {
+impl LegacyChainConfig {
   pub const SEED_PREFIX: &[u8] = b"chain_config";
+}
+
+impl From<&LegacyChainConfig> for ChainConfig {
+  fn from(legacy: &LegacyChainConfig) -> Self {
+    ChainConfig {
+      version: LayoutVersion,
+      domain_id: legacy.domain_id.into(),
+      chain_id: legacy.chain_id,
+      fee_adjustments: legacy.fee_adjustments.to_vec(),
+    }
+  }
+}
+
+/// The account layout of a (migrated) `ChainConfig`, so that transfers can read it without
+/// deserializing it, i.e. via `AccountLoader<'info, ZeroCopyChainConfig>`.
+//all fields are byte arrays so there's no padding and the layout matches the borsh one, i.e. the
+//  fee adjustments are prefixed by their count (see ChainConfig for why it's a vec)
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ZeroCopyChainConfig {
+  version:              u8,
+  domain_id:            [u8; 4],
+  chain_id:             [u8; 2],
+  fee_adjustment_count: [u8; 4],
+  fee_adjustments:      [ZeroCopyFeeAdjustment; FeeAdjustmentType::COUNT],
+}
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct ZeroCopyFeeAdjustment {
+  absolute_usd:         [u8; 4],
+  relative_percent_bps: [u8; 4],
+}
+
+impl ZeroCopyChainConfig {
+  //accounts that were created before the latest fee adjustment type was added are shorter until
+  //  they have been migrated, and legacy ones have a different layout altogether, so both are
+  //  deserialized instead (which treats the missing fee adjustments as unset)
+  pub fn view<'a>(chain_config: &'a AccountInfo) -> Result<AccountView<'a, Self>> {
+    view_account(
+      chain_config,
+      |chain_config: &Self| chain_config.version == ChainConfig::VERSION,
+      |data| Ok(Self::from(&*AnyChainConfig::try_deserialize(&mut &data[..])?)),
+    )
+  }
+
+  pub fn domain_id(&self) -> u32 {
+    u32::from_le_bytes(self.domain_id)
+  }
+
+  pub fn chain_id(&self) -> u16 {
+    u16::from_le_bytes(self.chain_id)
+  }
+
+  //same as ChainConfig::get_fee_adjustment
+  pub fn get_fee_adjustment(&self, adjustment_type: FeeAdjustmentType) -> FeeAdjustment {
+    let index = adjustment_type as usize;
+    if index >= u32::from_le_bytes(self.fee_adjustment_count) as usize {
+      return FeeAdjustment::NONE;
+    }
+
+    let adjustment = &self.fee_adjustments[index];
+    FeeAdjustment {
+      absolute_usd:         i32::from_le_bytes(adjustment.absolute_usd),
+      relative_percent_bps: u32::from_le_bytes(adjustment.relative_percent_bps),
+    }
+  }
+}
+
+impl ZeroCopy for ZeroCopyChainConfig {}
+
+impl Owner for ZeroCopyChainConfig {
+  fn owner() -> Pubkey {
+    crate::ID
+  }
+}
+
+impl Discriminator for ZeroCopyChainConfig {
+  const DISCRIMINATOR: &'static [u8] = ChainConfig::DISCRIMINATOR;
+}
+
+//clients see the same account as when it's deserialized
+#[cfg(feature = "idl-build")]
+impl IdlBuild for ZeroCopyChainConfig {
+  fn create_type() -> Option<IdlTypeDef> {
+    ChainConfig::create_type()
+  }
+
+  fn insert_types(types: &mut std::collections::BTreeMap<String, IdlTypeDef>) {
+    ChainConfig::insert_types(types)
+  }
+}
+
+impl From<&ChainConfig> for ZeroCopyChainConfig {
+  fn from(chain_config: &ChainConfig) -> Self {
+    let mut config = ZeroCopyChainConfig {
+      version:              ChainConfig::VERSION,
+      domain_id:            chain_config.domain_id.to_le_bytes(),
+      chain_id:             chain_config.chain_id.to_le_bytes(),
+      fee_adjustment_count: (chain_config.fee_adjustments.len() as u32).to_le_bytes(),
+      fee_adjustments:      Zeroable::zeroed(),
+    };
+    let adjustments = config.fee_adjustments.iter_mut().zip(&chain_config.fee_adjustments);
+    for (zero_copy, adjustment) in adjustments {
+      *zero_copy = ZeroCopyFeeAdjustment {
+        absolute_usd:         adjustment.absolute_usd.to_le_bytes(),
+        relative_percent_bps: adjustment.relative_percent_bps.to_le_bytes(),
+      };
+    }
+    config
+  }
+}
+
+/// An account as transfers see it: read in place if it has the current layout (just like via an
+/// `AccountLoader`), and upgraded to it otherwise, so that transfers keep working until the account
+/// has been migrated.
+pub enum AccountView<'a, T> {
+  InPlace(Ref<'a, T>),
+  Upgraded(T),
+}
+
+impl<T> Deref for AccountView<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    match self {
+      AccountView::InPlace(account) => account,
+      AccountView::Upgraded(account) => account,
+    }
+  }
+}
+
+//outdated accounts can't be read in place since they are shorter than the layout (or have a
+//  different one), the owner has to be checked by the caller
+fn view_account<'a, T: ZeroCopy>(
+  account: &'a AccountInfo,
+  is_current_version: impl FnOnce(&T) -> bool,
+  upgrade: impl FnOnce(&[u8]) -> Result<T>,
+) -> Result<AccountView<'a, T>> {
+  let data = account.try_borrow_data()?;
+  let layout = T::DISCRIMINATOR.len()..T::DISCRIMINATOR.len() + std::mem::size_of::<T>();
+  let is_current = data.starts_with(T::DISCRIMINATOR) &&
+    data.len() >= layout.end &&
+    is_current_version(bytemuck::from_bytes(&data[layout.clone()]));
+
+  if is_current {
+    Ok(AccountView::InPlace(Ref::map(data, |data| bytemuck::from_bytes(&data[layout]))))
+  }
+  else {
+    upgrade(&data).map(AccountView::Upgraded)
+  }
+}
+
+/// Read-only view of either a `ChainConfig` or a `LegacyChainConfig`, so that chains that haven't
+/// been migrated yet can still be configured and used for transfers.
+#[derive(Clone)]
+pub struct AnyChainConfig(ChainConfig);
+
+impl Deref for AnyChainConfig {
+  type Target = ChainConfig;
+
+  fn deref(&self) -> &ChainConfig {
+    &self.0
+  }
+}
+
+impl Owner for AnyChainConfig {
+  fn owner() -> Pubkey {
+    crate::ID
+  }
+}
+
+//clients should always see (and create) the current layout
+#[cfg(feature = "idl-build")]
+impl Discriminator for AnyChainConfig {
+  const DISCRIMINATOR: &'static [u8] = ChainConfig::DISCRIMINATOR;
+}
+
+#[cfg(feature = "idl-build")]
+impl IdlBuild for AnyChainConfig {
+  fn create_type() -> Option<IdlTypeDef> {
+    ChainConfig::create_type()
+  }
+}
+
+//deliberately a no-op so it can't be used to write to either account
+impl AccountSerialize for AnyChainConfig {}
+
+impl AccountDeserialize for AnyChainConfig {
+  fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+    if buf.starts_with(ChainConfig::DISCRIMINATOR) {
+      ChainConfig::try_deserialize(buf).map(AnyChainConfig)
+    }
+    else {
+      LegacyChainConfig::try_deserialize(buf).map(|legacy| AnyChainConfig((&legacy).into()))
+    }
+  }
+
+  fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+    ChainConfig::try_deserialize_unchecked(buf).map(AnyChainConfig)
+  }
+}
+
+//runs `f` on an account of this program with the given data
+#[cfg(test)]
+pub(crate) fn with_account_info<R>(data: &[u8], f: impl FnOnce(&AccountInfo) -> R) -> R {
+  let (key, owner, mut lamports, mut data) = (Pubkey::new_unique(), crate::ID, 0, data.to_vec());
+  let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+  f(&account)
+}
+
+#[test]
+fn zero_copy_chain_config_matches_the_account() {
+  assert_eq!(std::mem::size_of::<ZeroCopyChainConfig>(), ChainConfig::INIT_SPACE);
+
+  let mut chain_config = ChainConfig::new(7, 3);
+  let adjustment = FeeAdjustment { absolute_usd: -5, relative_percent_bps: 12_000 };
+  chain_config.set_fee_adjustment(FeeAdjustmentType::AvaxHop, adjustment.clone()).unwrap();
+  let mut data = Vec::new();
+  chain_config.try_serialize(&mut data).unwrap();
+  assert_eq!(data.len(), 8 + ChainConfig::INIT_SPACE);
+
+  with_account_info(&data, |account| {
+    let zero_copy = ZeroCopyChainConfig::view(account).unwrap();
+    //i.e. it's read in place rather than copied, which is what saves transfers compute units
+    assert!(matches!(zero_copy, AccountView::InPlace(_)));
+    assert_eq!(
+      bytemuck::bytes_of(&*zero_copy).as_ptr(),
+      account.try_borrow_data().unwrap()[8..].as_ptr(),
+    );
+    assert_eq!(
+      bytemuck::bytes_of(&*zero_copy),
+      bytemuck::bytes_of(&ZeroCopyChainConfig::from(&chain_config)),
+    );
+    assert_eq!(zero_copy.domain_id(), 7);
+    assert_eq!(zero_copy.chain_id(), 3);
+    assert_eq!(zero_copy.get_fee_adjustment(FeeAdjustmentType::AvaxHop), adjustment);
+    assert_eq!(zero_copy.get_fee_adjustment(FeeAdjustmentType::V1), FeeAdjustment::default());
+  });
+
+  //rejected rather than read out of bounds
+  data.truncate(data.len() - 1);
+  with_account_info(&data, |account| assert!(ZeroCopyChainConfig::view(account).is_err()));
+
+  let legacy = LegacyChainConfig {
+    domain_id: 2,
+    chain_id: 3,
+    fee_adjustments: [FeeAdjustment::IDENTITY; FeeAdjustmentType::COUNT],
+  };
+  let mut data = Vec::new();
+  legacy.try_serialize(&mut data).unwrap();
+  let any = AnyChainConfig::try_deserialize(&mut data.as_slice()).unwrap();
+  assert_eq!(any.domain_id, 2);
+  assert_eq!(any.get_fee_adjustment(FeeAdjustmentType::GasDropoff), &FeeAdjustment::IDENTITY);
+
+  //transfers to chains that haven't been migrated yet keep working
+  with_account_info(&data, |account| {
+    let upgraded = ZeroCopyChainConfig::view(account).unwrap();
+    assert!(matches!(upgraded, AccountView::Upgraded(_)));
+    assert_eq!(upgraded.domain_id(), 2);
+    assert_eq!(upgraded.chain_id(), 3);
+    assert_eq!(upgraded.get_fee_adjustment(FeeAdjustmentType::GasDropoff), FeeAdjustment::IDENTITY);
+  });
+}
+
+/// An integrator (e.g. a wallet or dApp) approved by governance to receive a share of the relay
+/// fee of the transfers it submits.
+#[account]
+#[derive(InitSpace)]
+pub struct Integrator {
+  pub version: LayoutVersion<{ Integrator::VERSION }>,
+  /// Receives the integrator's share of the fee (either directly or via its USDC ATA).
+  pub address: Pubkey,
+  /// The maximum share of the relay fee the integrator may request per transfer.
+  pub max_fee_share_bps: u16,
+}
+
+impl Integrator {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"integrator";
+}
+
+/// Partner-specific pricing for a user or an integrator, applied on top of the chain config's fee
+/// adjustments for on-chain quotes until it expires.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeDiscount {
+  pub version: LayoutVersion<{ FeeDiscount::VERSION }>,
+  /// The user or the integrator (address) the discount applies to.
+  pub subject: Pubkey,
+  pub expiration_time: u32,
+  //unset adjustments are the identity (i.e. no discount), see ChainConfig for why it's a vec
+  #[max_len(FEE_ADJUSTMENT_TYPE_COUNT)]
+  fee_adjustments: Vec<FeeAdjustment>,
+}
+
+impl FeeDiscount {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"fee_discount";
+
+  pub fn new(subject: Pubkey, expiration_time: u32) -> Self {
+    FeeDiscount {
+      version: LayoutVersion,
+      subject,
+      expiration_time,
+      fee_adjustments: vec![FeeAdjustment::IDENTITY; FeeAdjustmentType::COUNT],
+    }
+  }
 
   pub fn get_fee_adjustment(&self, adjustment_type: FeeAdjustmentType) -> &FeeAdjustment {
-    &self.fee_adjustments[adjustment_type as usize]
+    self.fee_adjustments.get(adjustment_type as usize).unwrap_or(&FeeAdjustment::IDENTITY)
   }
 
   pub fn set_fee_adjustment(
     &mut self,
     adjustment_type: FeeAdjustmentType,
     adjustment: FeeAdjustment
-  ) {
-    self.fee_adjustments[adjustment_type as usize] = adjustment;
+  ) -> Result<()> {
+    let fee_adjustment = self.fee_adjustments
+      .get_mut(adjustment_type as usize)
+      .ok_or(CctprError::AccountNotMigrated)?;
+    *fee_adjustment = adjustment;
+    Ok(())
+  }
+
+  pub fn fee_adjustment_count(&self) -> usize {
+    self.fee_adjustments.len()
+  }
+
+  pub(crate) fn pad_fee_adjustments(&mut self) {
+    self.fee_adjustments.resize(FeeAdjustmentType::COUNT, FeeAdjustment::IDENTITY);
+  }
+}
+
+/// Per chain bounds on the amount of a single transfer and a token bucket that caps the volume sent
+/// to the chain within a rolling window.
+///
+/// Chains without such an account are unlimited.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferLimits {
+  pub version: LayoutVersion<{ TransferLimits::VERSION }>,
+  pub domain_id: u32,
+  pub min_amount: u64,
+  pub max_amount: u64,
+  /// The volume that can be sent within any window, 0 disables the volume limit.
+  pub window_volume: u64,
+  /// In seconds, the bucket is refilled linearly over this duration.
+  pub window_duration: u32,
+  available_volume: u64,
+  last_update_time: u32,
+}
+
+impl TransferLimits {
+  pub const VERSION: u8 = 1;
+  //the domain id is used as a 4 byte big endian seed (same as for the chain config)
+  pub const SEED_PREFIX: &[u8] = b"transfer_limits";
+
+  pub fn set(
+    &mut self,
+    min_amount: u64,
+    max_amount: u64,
+    window_volume: u64,
+    window_duration: u32,
+    now: u32,
+  ) -> Result<()> {
+    require!(
+      min_amount <= max_amount && (window_volume == 0 || window_duration > 0),
+      CctprError::InvalidTransferLimits
+    );
+
+    //volume that was already used up stays used up, unless the limit was disabled before
+    let was_limited = self.window_volume != 0;
+    if was_limited {
+      self.refill(now);
+    }
+    self.min_amount = min_amount;
+    self.max_amount = max_amount;
+    self.window_volume = window_volume;
+    self.window_duration = window_duration;
+    self.available_volume =
+      if was_limited { self.available_volume.min(window_volume) } else { window_volume };
+    self.last_update_time = now;
+    Ok(())
+  }
+
+  pub fn available_volume(&self, now: u32) -> u64 {
+    if self.window_volume == 0 {
+      return u64::MAX;
+    }
+
+    let elapsed = now.saturating_sub(self.last_update_time);
+    let refill = u128::from(self.window_volume) * u128::from(elapsed) / u128::from(self.window_duration);
+    u128::from(self.available_volume).saturating_add(refill).min(self.window_volume.into()) as u64
+  }
+
+  pub fn consume(&mut self, amount: u64, now: u32) -> Result<()> {
+    require!(
+      self.min_amount <= amount && amount <= self.max_amount,
+      CctprError::TransferAmountOutOfBounds
+    );
+
+    if self.window_volume != 0 {
+      self.refill(now);
+      self.available_volume = self.available_volume
+        .checked_sub(amount)
+        .ok_or(CctprError::TransferVolumeLimitExceeded)?;
+    }
+    Ok(())
+  }
+
+  fn refill(&mut self, now: u32) {
+    self.available_volume = self.available_volume(now);
+    self.last_update_time = now;
+  }
+}
+
+#[test]
+fn transfer_volume_is_limited_per_window() {
+  let mut limits = TransferLimits {
+    version: LayoutVersion,
+    domain_id: 0,
+    min_amount: 0,
+    max_amount: 0,
+    window_volume: 0,
+    window_duration: 0,
+    available_volume: 0,
+    last_update_time: 0,
+  };
+  assert!(limits.set(10, 5, 0, 0, 1_000).is_err());
+  assert!(limits.set(0, 100, 1, 0, 1_000).is_err());
+
+  // 1_000 per 100 seconds, at most 600 per transfer:
+  limits.set(10, 600, 1_000, 100, 1_000).unwrap();
+  assert_eq!(limits.consume(9, 1_000).unwrap_err(), CctprError::TransferAmountOutOfBounds.into());
+  assert_eq!(limits.consume(601, 1_000).unwrap_err(), CctprError::TransferAmountOutOfBounds.into());
+
+  limits.consume(600, 1_000).unwrap();
+  limits.consume(400, 1_000).unwrap();
+  assert_eq!(limits.consume(10, 1_000).unwrap_err(), CctprError::TransferVolumeLimitExceeded.into());
+
+  // The bucket refills linearly and never beyond the window volume:
+  assert_eq!(limits.available_volume(1_025), 250);
+  limits.consume(250, 1_025).unwrap();
+  assert_eq!(limits.available_volume(1_025), 0);
+  assert_eq!(limits.available_volume(5_000), 1_000);
+
+  // Lowering the window volume doesn't reset what was already used:
+  limits.set(10, 600, 500, 100, 1_050).unwrap();
+  assert_eq!(limits.available_volume(1_050), 250);
+
+  // Disabling the volume limit only leaves the per transfer bounds:
+  limits.set(10, 600, 0, 0, 1_050).unwrap();
+  for _ in 0..10 {
+    limits.consume(600, 1_050).unwrap();
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum DenylistKind {
+  /// A Solana pubkey sending funds.
+  Sender,
+  /// A `mint_recipient` on any destination chain.
+  Recipient,
+}
+
+/// Marks a sender or a recipient as denylisted, i.e. an address is denylisted iff its entry exists.
+#[account]
+#[derive(InitSpace)]
+pub struct DenylistEntry {
+  pub version: LayoutVersion<{ DenylistEntry::VERSION }>,
+  pub kind:    DenylistKind,
+  pub address: [u8; 32],
+}
+
+impl DenylistEntry {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"denylist";
+
+  pub fn address(kind: DenylistKind, address: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[Self::SEED_PREFIX, &[kind as u8], address], &crate::ID).0
+  }
+
+  pub fn sender_address(sender: &Pubkey) -> Pubkey {
+    Self::address(DenylistKind::Sender, &sender.to_bytes())
+  }
+
+  pub fn recipient_address(mint_recipient: &[u8; 32]) -> Pubkey {
+    Self::address(DenylistKind::Recipient, mint_recipient)
   }
+
+  /// Whether an account fetched from the entry's address (if any) marks it as denylisted.
+  pub fn is_denylisted(entry: Option<&AccountInfo>) -> bool {
+    entry.is_some_and(|entry| !entry.data_is_empty())
+  }
+}
+
+/// Once enabled (i.e. with a non-zero delay), the offchain quoter, the fee recipient and large fee
+/// adjustment changes can only be updated via a `TimelockProposal`, which can be executed once
+/// `delay` seconds have passed. This gives watchers time to react to a stolen owner key.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Timelock {
+  /// In seconds.
+  pub delay: u32,
+  /// Fee adjustment changes up to these amounts still take effect instantly, see
+  /// `InstantChangeAllowance`.
+  pub max_instant_absolute_usd: u32,
+  pub max_instant_relative_percent_bps: u32,
+}
+
+impl Timelock {
+  pub fn is_enabled(&self) -> bool {
+    self.delay != 0
+  }
+
+  /// Whether replacing the timelock by `new` would make it easier to bypass, in which case the
+  ///   change itself has to go through a proposal.
+  pub fn is_loosened_by(&self, new: &Timelock) -> bool {
+    self.is_enabled() && (
+      new.delay < self.delay ||
+      new.max_instant_absolute_usd > self.max_instant_absolute_usd ||
+      new.max_instant_relative_percent_bps > self.max_instant_relative_percent_bps
+    )
+  }
+
+  /// Deducts the change from the allowance, fails if the change is too large to take effect
+  ///   instantly, i.e. if it has to be proposed instead.
+  pub fn consume_instant_change(
+    &self,
+    allowance: &mut InstantChangeAllowance,
+    old: &FeeAdjustment,
+    new: &FeeAdjustment,
+    now: i64,
+  ) -> Result<()> {
+    if !self.is_enabled() {
+      return Ok(());
+    }
+
+    //refilled linearly over the delay, same as the volume of `TransferLimits`
+    let elapsed = u64::try_from(now.saturating_sub(allowance.last_update_time)).unwrap_or(0);
+    let refill = |available: u32, max: u32| {
+      let refilled = u64::from(available) + u64::from(max) * elapsed / u64::from(self.delay);
+      refilled.min(max.into()) as u32
+    };
+    let available_absolute_usd = refill(allowance.absolute_usd, self.max_instant_absolute_usd);
+    let available_relative_percent_bps =
+      refill(allowance.relative_percent_bps, self.max_instant_relative_percent_bps);
+
+    let absolute_usd = old.absolute_usd.abs_diff(new.absolute_usd);
+    let relative_percent_bps = old.relative_percent_bps.abs_diff(new.relative_percent_bps);
+    require!(
+      absolute_usd <= available_absolute_usd &&
+        relative_percent_bps <= available_relative_percent_bps,
+      CctprError::TimelockRequired
+    );
+
+    *allowance = InstantChangeAllowance {
+      absolute_usd:         available_absolute_usd - absolute_usd,
+      relative_percent_bps: available_relative_percent_bps - relative_percent_bps,
+      last_update_time:     now,
+    };
+    Ok(())
+  }
+}
+
+/// What's left of the instant fee adjustment changes permitted by the timelock, shared by all fee
+/// adjustments, so that a series of small changes can't add up to a large one. It's refilled up to
+/// the max over the course of the delay, hence at most twice the max can be changed within it.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InstantChangeAllowance {
+  pub absolute_usd:         u32,
+  pub relative_percent_bps: u32,
+  pub last_update_time:     i64,
+}
+
+/// A governance action that has to be proposed and can only be executed after the timelock delay.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, PartialEq, Eq)]
+pub enum TimelockedAction {
+  UpdateOffchainQuoter { new_offchain_quoter: [u8; 20] },
+  UpdateFeeRecipient { new_fee_recipient: Pubkey },
+  UpdateFeeAdjustment {
+    domain_id:          u32,
+    adjustment_type:    FeeAdjustmentType,
+    new_fee_adjustment: FeeAdjustment,
+  },
+  UpdateTimelock { new_timelock: Timelock },
+}
+
+impl TimelockedAction {
+  /// Identifies what the action updates, so that there can be at most one pending proposal per
+  ///   updated value.
+  pub fn seed(&self) -> [u8; 6] {
+    let mut seed = [0; 6];
+    match self {
+      TimelockedAction::UpdateOffchainQuoter { .. } => seed[0] = 0,
+      TimelockedAction::UpdateFeeRecipient { .. } => seed[0] = 1,
+      TimelockedAction::UpdateFeeAdjustment { domain_id, adjustment_type, .. } => {
+        seed[0] = 2;
+        seed[1..5].copy_from_slice(&domain_id.to_be_bytes());
+        seed[5] = *adjustment_type as u8;
+      }
+      TimelockedAction::UpdateTimelock { .. } => seed[0] = 3,
+    }
+    seed
+  }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TimelockProposal {
+  pub version:       LayoutVersion<{ TimelockProposal::VERSION }>,
+  /// Gets the rent back once the proposal is executed or cancelled.
+  pub proposer:      Pubkey,
+  pub executable_at: i64,
+  /// Afterwards, the proposal can only be cancelled, which anyone can do.
+  pub expires_at:    i64,
+  pub action:        TimelockedAction,
+}
+
+impl TimelockProposal {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"timelock_proposal";
+  /// How long a proposal remains executable once its delay has passed, in seconds.
+  pub const EXECUTION_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+  pub fn is_expired(&self, now: i64) -> bool {
+    now > self.expires_at
+  }
+}
+
+#[test]
+fn instant_fee_adjustment_changes_are_rate_limited() {
+  let timelock = Timelock {
+    delay: 86_400,
+    max_instant_absolute_usd: 100_000,
+    max_instant_relative_percent_bps: 500,
+  };
+  let now = 1_700_000_000;
+  let mut allowance = InstantChangeAllowance::default();
+  let old = FeeAdjustment { absolute_usd: -50_000, relative_percent_bps: 10_000 };
+
+  let large_absolute = FeeAdjustment { absolute_usd: 50_001, ..old };
+  assert!(timelock.consume_instant_change(&mut allowance, &old, &large_absolute, now).is_err());
+  let large_relative = FeeAdjustment { relative_percent_bps: 10_501, ..old };
+  assert!(timelock.consume_instant_change(&mut allowance, &old, &large_relative, now).is_err());
+  let mut disabled_allowance = InstantChangeAllowance::default();
+  assert!(Timelock::default()
+    .consume_instant_change(&mut disabled_allowance, &old, &large_relative, now)
+    .is_ok());
+
+  // Small changes can't add up to a large one:
+  let small = FeeAdjustment { absolute_usd: 0, relative_percent_bps: 9_750 };
+  assert!(timelock.consume_instant_change(&mut allowance, &old, &small, now).is_ok());
+  let next = FeeAdjustment { absolute_usd: 50_000, relative_percent_bps: 9_500 };
+  assert!(timelock.consume_instant_change(&mut allowance, &small, &next, now).is_ok());
+  let tiny = FeeAdjustment { absolute_usd: 50_001, ..next };
+  assert_eq!(
+    timelock.consume_instant_change(&mut allowance, &next, &tiny, now).unwrap_err(),
+    CctprError::TimelockRequired.into(),
+  );
+
+  // The allowance is refilled over the course of the delay:
+  let later = now + i64::from(timelock.delay) / 2;
+  let half = FeeAdjustment { absolute_usd: 100_000, relative_percent_bps: 9_250 };
+  assert!(timelock.consume_instant_change(&mut allowance, &next, &half, later).is_ok());
+  assert_eq!(allowance, InstantChangeAllowance {
+    absolute_usd: 0,
+    relative_percent_bps: 0,
+    last_update_time: later,
+  });
+  let much_later = later + 10 * i64::from(timelock.delay);
+  assert!(timelock.consume_instant_change(&mut allowance, &half, &old, much_later).is_err());
+  assert!(timelock.consume_instant_change(&mut allowance, &half, &next, much_later).is_ok());
+
+  assert!(timelock.is_loosened_by(&Timelock { delay: 3_600, ..timelock }));
+  assert!(timelock.is_loosened_by(&Timelock { max_instant_relative_percent_bps: 501, ..timelock }));
+  assert!(!timelock.is_loosened_by(&Timelock { delay: 172_800, max_instant_absolute_usd: 0, ..timelock }));
+  assert!(!Timelock::default().is_loosened_by(&timelock));
+}
+
+//the multisig rules are shared with the price oracle
+pub use price_oracle::state::MAX_MULTISIG_SIGNERS;
+
+/// An M-of-N multisig that can act as the owner.
+///
+/// The multisig doesn't sign anything itself: its authority, a data-less PDA derived from it (see
+/// `Multisig::authority`), is what gets set as the owner. Once a `MultisigProposal` has collected
+/// `threshold` approvals, its instruction is invoked on this program with the authority as signer.
+/// Since the authority also pays for the accounts created by the owner, it has to be funded.
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+  pub version:        LayoutVersion<{ Multisig::VERSION }>,
+  /// Arbitrary key making the address unique, so that several multisigs can coexist.
+  pub create_key:     Pubkey,
+  #[max_len(MAX_MULTISIG_SIGNERS)]
+  pub signers:        Vec<Pubkey>,
+  pub threshold:      u8,
+  /// The index of the next proposal.
+  pub proposal_count: u64,
+  /// Incremented whenever the signers or the threshold change, which invalidates all pending
+  /// proposals.
+  pub generation:     u64,
+}
+
+impl Multisig {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"multisig";
+  pub const AUTHORITY_SEED_PREFIX: &[u8] = b"multisig_authority";
+
+  pub fn authority(multisig: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Self::AUTHORITY_SEED_PREFIX, multisig.as_ref()], &crate::ID)
+  }
+
+  pub fn is_signer(&self, account: &Pubkey) -> bool {
+    self.signers.contains(account)
+  }
+
+  pub fn is_valid(signers: &[Pubkey], threshold: u8) -> bool {
+    MultisigState::is_valid(signers, threshold)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposedAccountMeta {
+  pub pubkey:      Pubkey,
+  pub is_signer:   bool,
+  pub is_writable: bool,
+}
+
+/// An instruction of this program proposed to a `Multisig`.
+#[account]
+pub struct MultisigProposal {
+  pub version:    LayoutVersion<{ MultisigProposal::VERSION }>,
+  pub multisig:   Pubkey,
+  /// The index of the proposal within the multisig, used to derive its address.
+  pub index:      u64,
+  /// Gets the rent back once the proposal is executed or cancelled.
+  pub proposer:   Pubkey,
+  /// The generation of the multisig the proposal was made for, see `Multisig::generation`.
+  pub generation: u64,
+  pub expires_at: i64,
+  pub accounts:   Vec<ProposedAccountMeta>,
+  pub data:       Vec<u8>,
+  pub approvals:  Vec<Pubkey>,
+}
+
+impl MultisigProposal {
+  pub const VERSION: u8 = 1;
+  pub const SEED_PREFIX: &[u8] = b"multisig_proposal";
+  /// How long a proposal can be approved and executed, in seconds.
+  pub const LIFETIME: i64 = StaleProposal::LIFETIME;
+
+  //the approvals are allocated upfront, so approving never needs to grow the account
+  pub fn space(account_count: usize, data_len: usize) -> usize {
+    8 + 1 + 32 + 8 + 32 + 8 + 8 +
+      4 + account_count * (32 + 1 + 1) +
+      4 + data_len +
+      4 + MAX_MULTISIG_SIGNERS * 32
+  }
+
+  /// Whether the proposal can still be approved and executed, otherwise it can only be cancelled,
+  ///   which anyone can do then.
+  pub fn check_pending(&self, multisig: &Multisig, now: i64) -> Result<()> {
+    StaleProposal::check(self.generation, self.expires_at, multisig.generation, now)
+      .map_err(|stale| CctprError::from(stale).into())
+  }
+
+  //approvals of accounts that are no longer signers of the multisig don't count
+  pub fn approval_count(&self, multisig: &Multisig) -> usize {
+    self.approvals.iter().filter(|approval| multisig.is_signer(approval)).count()
+  }
+
+  pub fn instruction(&self) -> Instruction {
+    Instruction {
+      program_id: crate::ID,
+      accounts: self.accounts.iter().map(|meta| AccountMeta {
+        pubkey:      meta.pubkey,
+        is_signer:   meta.is_signer,
+        is_writable: meta.is_writable,
+      }).collect(),
+      data: self.data.clone(),
+    }
+  }
+}
+
+#[test]
+fn multisig_approvals_are_counted_for_current_signers_only() {
+  let signers = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+  assert!(Multisig::is_valid(&signers, 2));
+  assert!(!Multisig::is_valid(&signers, 0));
+  assert!(!Multisig::is_valid(&signers, 4));
+  assert!(!Multisig::is_valid(&[signers[0], signers[0]], 1));
+
+  let mut multisig = Multisig {
+    version: LayoutVersion,
+    create_key: Pubkey::new_unique(),
+    signers: signers.to_vec(),
+    threshold: 2,
+    proposal_count: 0,
+    generation: 0,
+  };
+  let now = 1_700_000_000;
+  let proposal = MultisigProposal {
+    version: LayoutVersion,
+    multisig: Pubkey::new_unique(),
+    index: 0,
+    proposer: signers[0],
+    generation: 0,
+    expires_at: now + MultisigProposal::LIFETIME,
+    accounts: vec![],
+    data: vec![1, 2, 3],
+    approvals: vec![signers[0], signers[1]],
+  };
+  assert_eq!(proposal.approval_count(&multisig), 2);
+  assert_eq!(
+    MultisigProposal::space(0, 3),
+    8 + proposal.try_to_vec().unwrap().len() + (MAX_MULTISIG_SIGNERS - 2) * 32,
+  );
+
+  assert!(proposal.check_pending(&multisig, now).is_ok());
+  assert_eq!(
+    proposal.check_pending(&multisig, proposal.expires_at + 1).unwrap_err(),
+    CctprError::ProposalExpired.into(),
+  );
+
+  multisig.signers[1] = Pubkey::new_unique();
+  assert_eq!(proposal.approval_count(&multisig), 1);
+
+  // Changing the signers invalidates pending proposals:
+  multisig.generation += 1;
+  assert_eq!(
+    proposal.check_pending(&multisig, now).unwrap_err(),
+    CctprError::ProposalOutdated.into(),
+  );
 }
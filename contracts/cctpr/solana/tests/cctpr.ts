@@ -1,4 +1,4 @@
-import { describe, test, before, beforeEach } from "node:test";
+import { describe, test, before, beforeEach, after } from "node:test";
 import assert from "node:assert";
 import util from 'node:util';
 import type { Layout } from "binary-layout";
@@ -41,6 +41,7 @@ import {
   mulPercentage,
   eth,
   usdcContracts,
+  domainIdOf,
   v1,
   v2,
 } from "@stable-io/cctp-sdk-definitions";
@@ -78,6 +79,7 @@ import {
   CctpR,
   CctpRReclaim,
   relayRequestEventLayout,
+  chainConfigLayout,
   oracle,
 } from "@stable-io/cctp-sdk-cctpr-solana";
 import type {
@@ -509,6 +511,13 @@ describe("CctpR", function() {
                 [mode, inOrOut, corridor, withGasDropoff, useOnChainQuote] as const
               );
 
+    //compute units consumed by each transfer, printed at the end so that the cost of changes to
+    //  the transfer instruction (e.g. how accounts are read) can be compared across builds
+    const computeUnits: Record<string, bigint> = {};
+    after(() => console.table(
+      Object.entries(computeUnits).map(([testCase, units]) => ({ testCase, units }))
+    ));
+
 
     const eventDataLayout = {
       v1:       v1SentEventDataLayout,
@@ -638,6 +647,7 @@ describe("CctpR", function() {
 
           const txResult = await assertSuccess(sendableTx);
           // console.log("logs:", txResult.logs().join("\n"));
+          computeUnits[stringifyTestCase(testCase)] = txResult.computeUnitsConsumed();
 
           //check cpi event
           const cpiEvents = filterAnchorCpiEvents(txResult);
@@ -817,6 +827,17 @@ describe("CctpR", function() {
       });
     });
 
+    test("chain registration", async () => {
+      //chain configs are derived from the 4 byte big endian domain id
+      const domainSeed = serialize({ binary: "uint", size: 4 }, domainIdOf(destinationDomain));
+      const chainConfig = findPda(["chain_config", domainSeed], cctprProgramId)[0];
+
+      assert.deepStrictEqual(
+        await getDeserializedAccount(chainConfig, chainConfigLayout(network)),
+        { domain: destinationDomain, oracleChain: destinationDomain, feeAdjustments },
+      );
+    });
+
     test("chain deregistration", async () => {
       const deregisterIx = await governance.composeDeregisterChainIx("Avalanche");
       await assertSuccess(createAndSendTx([deregisterIx], cctprOwnerKp));
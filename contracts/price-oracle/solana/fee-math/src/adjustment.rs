@@ -0,0 +1,99 @@
+use crate::int::{Int, Rounding};
+
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// Scales a fee and then adds (or subtracts) a flat amount to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeAdjustment {
+    /// In μusd, a negative adjustment can't make the fee negative.
+    pub absolute_usd: i32,
+    pub relative_percent_bps: u32,
+}
+
+impl FeeAdjustment {
+    pub const IDENTITY: FeeAdjustment = FeeAdjustment {
+        absolute_usd: 0,
+        relative_percent_bps: BASIS_POINTS as u32,
+    };
+
+    pub fn apply(&self, micro_usd: u64) -> Int<u64> {
+        // Rounded up so that the adjusted fee never undershoots.
+        let with_relative = Int::Ok(micro_usd).mul_div_rounded(
+            self.relative_percent_bps.into(),
+            BASIS_POINTS,
+            Rounding::Ceil,
+        );
+        let absolute_usd = u64::from(self.absolute_usd.unsigned_abs());
+        if self.absolute_usd >= 0 {
+            with_relative + absolute_usd
+        } else {
+            match with_relative {
+                Int::Ok(value) => Int::Ok(value.saturating_sub(absolute_usd)),
+                err => err,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FeeError;
+    use proptest::prelude::*;
+
+    fn adjustments() -> impl Strategy<Value = FeeAdjustment> {
+        (
+            any::<i32>(),
+            prop_oneof![0..=2 * BASIS_POINTS as u32, any::<u32>()],
+        )
+            .prop_map(|(absolute_usd, relative_percent_bps)| FeeAdjustment {
+                absolute_usd,
+                relative_percent_bps,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn apply_matches_wide_arithmetic(adjustment in adjustments(), micro_usd: u64) {
+            let with_relative = (u128::from(micro_usd) * u128::from(adjustment.relative_percent_bps))
+                .div_ceil(u128::from(BASIS_POINTS));
+            let expected = if adjustment.absolute_usd >= 0 {
+                u64::try_from(with_relative + adjustment.absolute_usd as u128)
+            } else {
+                u64::try_from(with_relative).map(|with_relative| {
+                    with_relative.saturating_sub(adjustment.absolute_usd.unsigned_abs().into())
+                })
+            };
+
+            prop_assert_eq!(
+                adjustment.apply(micro_usd).into_result(),
+                expected.map_err(|_| FeeError::Overflow)
+            );
+        }
+
+        #[test]
+        fn apply_is_monotonic(adjustment in adjustments(), a: u64, b: u64) {
+            let (lower, upper) = (a.min(b), a.max(b));
+            match (adjustment.apply(lower).into_result(), adjustment.apply(upper).into_result()) {
+                (Ok(lower), Ok(upper)) => prop_assert!(lower <= upper),
+                (Err(_), Ok(_)) => prop_assert!(false, "only the larger fee may overflow"),
+                _ => {}
+            }
+        }
+
+        #[test]
+        fn negative_adjustments_only_lower_the_fee(
+            absolute_usd in i32::MIN..=0,
+            micro_usd in 0..u64::MAX / BASIS_POINTS,
+        ) {
+            let adjustment = FeeAdjustment {
+                absolute_usd,
+                ..FeeAdjustment::IDENTITY
+            };
+            let adjusted = adjustment.apply(micro_usd).into_result().unwrap();
+
+            prop_assert!(adjusted <= micro_usd);
+            prop_assert!(adjusted >= micro_usd.saturating_sub(absolute_usd.unsigned_abs().into()));
+        }
+    }
+}
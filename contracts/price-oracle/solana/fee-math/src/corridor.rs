@@ -0,0 +1,251 @@
+//! What cctpr charges for relaying a transfer when it quotes on-chain.
+
+use crate::{
+    adjustment::FeeAdjustment,
+    int::{Int, Rounding},
+    prices::{ChainPrices, TargetChainCosts, USD18_PER_MICRO_USD},
+    FeeError,
+};
+
+// Taken from the EVM contract.
+pub const AVAX_HOP_GAS_COST: u32 = 281_200;
+
+pub const EVM_GAS_DROPOFF_GAS_COST: u32 = 22_000;
+pub const EVM_V1_BILLED_SIZE: u32 = 664;
+pub const EVM_V1_GAS_COST: u32 = 165_000;
+pub const EVM_V2_BILLED_SIZE: u32 = 793;
+pub const EVM_V2_GAS_COST: u32 = 175_000;
+
+pub const SUI_GAS_DROPOFF_STORAGE_BYTES: u32 = 260;
+pub const SUI_GAS_DROPOFF_COMPUTE_BUDGET: u32 = 1_000;
+pub const SUI_GAS_DROPOFF_STORAGE_REBATE: u32 = 260;
+pub const SUI_COMPUTE_BUDGET: u32 = 2_000;
+pub const SUI_STORAGE_BYTES: u32 = 2_363;
+pub const SUI_STORAGE_REBATE: u32 = 1_979;
+
+/// The costs of the hop through Avalanche, charged with the Avalanche prices.
+pub const AVAX_HOP_COSTS: TargetChainCosts = TargetChainCosts {
+    evm_transaction_gas: AVAX_HOP_GAS_COST,
+    evm_transaction_size: 0,
+    sui_computation_units: 0,
+    sui_stored_bytes: 0,
+    sui_deleted_bytes: 0,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corridor {
+    V1,
+    V2Direct,
+    AvaxHop,
+}
+
+impl Corridor {
+    /// The costs of executing the transfer on the destination chain.
+    pub fn destination_costs(self, with_gas_dropoff: bool) -> TargetChainCosts {
+        let (evm_transaction_gas, evm_transaction_size) = if self == Corridor::V2Direct {
+            (EVM_V2_GAS_COST, EVM_V2_BILLED_SIZE)
+        } else {
+            (EVM_V1_GAS_COST, EVM_V1_BILLED_SIZE)
+        };
+
+        if with_gas_dropoff {
+            TargetChainCosts {
+                evm_transaction_gas: evm_transaction_gas + EVM_GAS_DROPOFF_GAS_COST,
+                evm_transaction_size,
+                sui_computation_units: SUI_COMPUTE_BUDGET + SUI_GAS_DROPOFF_COMPUTE_BUDGET,
+                sui_stored_bytes: SUI_STORAGE_BYTES + SUI_GAS_DROPOFF_STORAGE_BYTES,
+                sui_deleted_bytes: SUI_STORAGE_REBATE + SUI_GAS_DROPOFF_STORAGE_REBATE,
+            }
+        } else {
+            TargetChainCosts {
+                evm_transaction_gas,
+                evm_transaction_size,
+                sui_computation_units: SUI_COMPUTE_BUDGET,
+                sui_stored_bytes: SUI_STORAGE_BYTES,
+                sui_deleted_bytes: SUI_STORAGE_REBATE,
+            }
+        }
+    }
+}
+
+/// Everything an on-chain relay quote depends on.
+#[derive(Clone, Copy, Debug)]
+pub struct RelayFeeParams<'a> {
+    pub corridor: Corridor,
+    pub gas_dropoff_micro_gas_token: u32,
+
+    /// Only needed for the Avalanche hop.
+    pub avalanche_prices: Option<&'a ChainPrices>,
+    /// Only needed if the corresponding fee adjustments aren't purely absolute.
+    pub destination_prices: Option<&'a ChainPrices>,
+
+    /// The fee adjustment of the corridor on the destination chain.
+    pub corridor_adjustment: FeeAdjustment,
+    /// The fee adjustment of gas dropoffs on the destination chain.
+    pub gas_dropoff_adjustment: FeeAdjustment,
+
+    /// Applied on top of `corridor_adjustment`, e.g. a partner discount.
+    pub corridor_discount: Option<FeeAdjustment>,
+    /// Applied on top of `gas_dropoff_adjustment`.
+    pub gas_dropoff_discount: Option<FeeAdjustment>,
+}
+
+/// The relay fee of a transfer in μusd.
+pub fn relay_fee_micro_usd(params: &RelayFeeParams) -> Result<u64, FeeError> {
+    let execution_fee_usd18 = |prices: Option<&ChainPrices>, costs: TargetChainCosts| {
+        Ok(prices
+            .ok_or(FeeError::MissingPrices)?
+            .total_fee_usd18(&costs, 0, 0))
+    };
+    let adjust = |adjustment: FeeAdjustment, discount: Option<FeeAdjustment>, micro_usd: u64| {
+        let adjusted = adjustment.apply(micro_usd).into_result()?;
+        discount.map_or(Ok(adjusted), |discount| {
+            discount.apply(adjusted).into_result()
+        })
+    };
+
+    let avax_hop_execution_fee_usd18 = if params.corridor == Corridor::AvaxHop {
+        execution_fee_usd18(params.avalanche_prices, AVAX_HOP_COSTS)?
+    } else {
+        Int::Ok(0)
+    };
+
+    let destination_execution_fee_usd18 = if params.corridor_adjustment.relative_percent_bps != 0 {
+        let costs = params
+            .corridor
+            .destination_costs(params.gas_dropoff_micro_gas_token != 0);
+        execution_fee_usd18(params.destination_prices, costs)?
+    } else {
+        Int::Ok(0)
+    };
+
+    // Summed before rounding up so that a two-leg corridor isn't charged up to an extra μusd.
+    let execution_fee_micro_usd = (avax_hop_execution_fee_usd18 + destination_execution_fee_usd18)
+        .div_rounded(USD18_PER_MICRO_USD, Rounding::Ceil)
+        .narrow()
+        .into_result()?;
+
+    let total_execution_fee_micro_usd = adjust(
+        params.corridor_adjustment,
+        params.corridor_discount,
+        execution_fee_micro_usd,
+    )?;
+
+    let gas_dropoff_fee_micro_usd = if params.gas_dropoff_micro_gas_token > 0 {
+        let unadjusted_micro_usd = if params.gas_dropoff_adjustment.relative_percent_bps != 0 {
+            params
+                .destination_prices
+                .ok_or(FeeError::MissingPrices)?
+                .micro_gas_token_to_micro_usd(params.gas_dropoff_micro_gas_token)
+                .into_result()?
+        } else {
+            0
+        };
+
+        adjust(
+            params.gas_dropoff_adjustment,
+            params.gas_dropoff_discount,
+            unadjusted_micro_usd,
+        )?
+    } else {
+        0
+    };
+
+    (Int::Ok(total_execution_fee_micro_usd) + gas_dropoff_fee_micro_usd).into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prices::PlatformPrices;
+
+    #[test]
+    fn relay_fee_adds_up_the_corridor_components() {
+        let avalanche_prices = ChainPrices {
+            gas_token_price: 20_000_000, // 20 USD per AVAX
+            platform: PlatformPrices::Evm {
+                gas_price: 25_000, // 25 gwei
+                price_per_tx_byte: 0,
+            },
+            precision: 0,
+        };
+        let destination_prices = ChainPrices {
+            gas_token_price: 2_000_000_000, // 2000 USD per ETH
+            platform: PlatformPrices::Evm {
+                gas_price: 1_000, // 1 gwei
+                price_per_tx_byte: 0,
+            },
+            precision: 0,
+        };
+        let mut params = RelayFeeParams {
+            corridor: Corridor::AvaxHop,
+            gas_dropoff_micro_gas_token: 0,
+            avalanche_prices: Some(&avalanche_prices),
+            destination_prices: Some(&destination_prices),
+            corridor_adjustment: FeeAdjustment::IDENTITY,
+            gas_dropoff_adjustment: FeeAdjustment::IDENTITY,
+            corridor_discount: None,
+            gas_dropoff_discount: None,
+        };
+
+        // 281_200 gas * 25_000 Mwei/gas * 20 USD/AVAX = 0.1406 USD
+        let avax_hop_micro_usd = 140_600;
+        // 165_000 gas * 1_000 Mwei/gas * 2000 USD/ETH = 0.33 USD
+        let destination_micro_usd = 330_000;
+        assert_eq!(
+            relay_fee_micro_usd(&params),
+            Ok(avax_hop_micro_usd + destination_micro_usd)
+        );
+
+        // 1_000 µETH * 2000 USD/ETH = 2 USD, with the destination execution of a gas dropoff:
+        params.gas_dropoff_micro_gas_token = 1_000;
+        let destination_micro_usd = 374_000;
+        assert_eq!(
+            relay_fee_micro_usd(&params),
+            Ok(avax_hop_micro_usd + destination_micro_usd + 2_000_000)
+        );
+
+        // A purely absolute adjustment doesn't need the destination prices:
+        params.corridor = Corridor::V1;
+        params.gas_dropoff_micro_gas_token = 0;
+        params.destination_prices = None;
+        params.corridor_adjustment = FeeAdjustment {
+            absolute_usd: 100_000,
+            relative_percent_bps: 0,
+        };
+        params.corridor_discount = Some(FeeAdjustment {
+            absolute_usd: 0,
+            relative_percent_bps: 5_000,
+        });
+        assert_eq!(relay_fee_micro_usd(&params), Ok(50_000));
+
+        params.corridor_adjustment = FeeAdjustment::IDENTITY;
+        assert_eq!(relay_fee_micro_usd(&params), Err(FeeError::MissingPrices));
+    }
+
+    #[test]
+    fn relay_fee_rounds_the_corridor_legs_once() {
+        // 1 Mwei at 1 μusd per token makes each leg cost a fraction of a μusd.
+        let cheap_prices = ChainPrices {
+            gas_token_price: 1,
+            platform: PlatformPrices::Evm {
+                gas_price: 1,
+                price_per_tx_byte: 0,
+            },
+            precision: 0,
+        };
+        let params = RelayFeeParams {
+            corridor: Corridor::AvaxHop,
+            gas_dropoff_micro_gas_token: 0,
+            avalanche_prices: Some(&cheap_prices),
+            destination_prices: Some(&cheap_prices),
+            corridor_adjustment: FeeAdjustment::IDENTITY,
+            gas_dropoff_adjustment: FeeAdjustment::IDENTITY,
+            corridor_discount: None,
+            gas_dropoff_discount: None,
+        };
+
+        // (281_200 + 165_000) usd18 rounds up to a single μusd rather than one per leg.
+        assert_eq!(relay_fee_micro_usd(&params), Ok(1));
+    }
+}
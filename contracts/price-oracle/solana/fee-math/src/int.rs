@@ -0,0 +1,385 @@
+use crate::FeeError;
+use core::ops::{Add, Div, Mul, Sub};
+
+pub enum Int<T> {
+    Ok(T),
+    Overflow,
+    DivisionByZero,
+}
+
+/// How a division handles its remainder. The plain `/` operator truncates, i.e. rounds down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    HalfUp,
+}
+
+macro_rules! impl_int_op {
+    ($self:ty, $other:ty, $out:ty) => {
+        impl Mul<$other> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn mul(self, other: $other) -> Self::Output {
+                match self {
+                    Int::Ok(value) => {
+                        match (<$out>::from(value)).checked_mul(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    Int::Overflow => Int::Overflow,
+                    Int::DivisionByZero => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Mul<Int<$other>> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn mul(self, other: Int<$other>) -> Self::Output {
+                match (self, other) {
+                    (Int::Ok(value), Int::Ok(other)) => {
+                        match (<$out>::from(value)).checked_mul(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    (Int::Ok(_), Int::Overflow) => Int::Overflow,
+                    (Int::Ok(_), Int::DivisionByZero) => Int::DivisionByZero,
+                    (Int::Overflow, _) => Int::Overflow,
+                    (Int::DivisionByZero, _) => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Add<$other> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn add(self, other: $other) -> Self::Output {
+                match self {
+                    Int::Ok(value) => {
+                        match (<$out>::from(value)).checked_add(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    Int::Overflow => Int::Overflow,
+                    Int::DivisionByZero => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Add<Int<$other>> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn add(self, other: Int<$other>) -> Self::Output {
+                match (self, other) {
+                    (Int::Ok(value), Int::Ok(other)) => {
+                        match (<$out>::from(value)).checked_add(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    (Int::Ok(_), Int::Overflow) => Int::Overflow,
+                    (Int::Ok(_), Int::DivisionByZero) => Int::DivisionByZero,
+                    (Int::Overflow, _) => Int::Overflow,
+                    (Int::DivisionByZero, _) => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Sub<$other> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn sub(self, other: $other) -> Self::Output {
+                match self {
+                    Int::Ok(value) => {
+                        match (<$out>::from(value)).checked_sub(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    Int::Overflow => Int::Overflow,
+                    Int::DivisionByZero => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Sub<Int<$other>> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn sub(self, other: Int<$other>) -> Self::Output {
+                match (self, other) {
+                    (Int::Ok(value), Int::Ok(other)) => {
+                        match (<$out>::from(value)).checked_sub(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::Overflow,
+                        }
+                    }
+                    (Int::Ok(_), Int::Overflow) => Int::Overflow,
+                    (Int::Ok(_), Int::DivisionByZero) => Int::DivisionByZero,
+                    (Int::Overflow, _) => Int::Overflow,
+                    (Int::DivisionByZero, _) => Int::DivisionByZero,
+                }
+            }
+        }
+
+        impl Div<$other> for Int<$self> {
+            type Output = Int<$out>;
+
+            fn div(self, other: $other) -> Self::Output {
+                match self {
+                    Int::Ok(value) => {
+                        match (<$out>::from(value)).checked_div(<$out>::from(other)) {
+                            Some(result) => Int::Ok(result),
+                            None => Int::DivisionByZero,
+                        }
+                    }
+                    Int::Overflow => Int::Overflow,
+                    Int::DivisionByZero => Int::DivisionByZero,
+                }
+            }
+        }
+    };
+}
+
+impl_int_op!(u64, u64, u64);
+impl_int_op!(u64, u32, u64);
+impl_int_op!(u64, u128, u128);
+impl_int_op!(u32, u32, u32);
+impl_int_op!(u32, u8, u32);
+impl_int_op!(u64, u8, u64);
+impl_int_op!(u32, u64, u64);
+impl_int_op!(u128, u128, u128);
+impl_int_op!(u128, u64, u128);
+impl_int_op!(u128, u32, u128);
+
+macro_rules! impl_int {
+    ($self:ty, $up:ty) => {
+        impl Int<$self> {
+            pub fn mul_div(self, numerator: $self, denominator: $self) -> Self {
+                self.mul_div_rounded(numerator, denominator, Rounding::Floor)
+            }
+
+            /// Multiplies and divides without overflowing in between, then rounds as requested.
+            pub fn mul_div_rounded(
+                self,
+                numerator: $self,
+                denominator: $self,
+                rounding: Rounding,
+            ) -> Self {
+                let Self::Ok(value) = self else {
+                    return self;
+                };
+
+                match Int::Ok(<$up>::from(value) * <$up>::from(numerator))
+                    .div_rounded(<$up>::from(denominator), rounding)
+                {
+                    Int::Ok(result) => match <$self>::try_from(result) {
+                        Ok(result) => Int::Ok(result),
+                        Err(_) => Int::Overflow,
+                    },
+                    Int::Overflow => Int::Overflow,
+                    Int::DivisionByZero => Int::DivisionByZero,
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_int_div {
+    ($self:ty) => {
+        impl Int<$self> {
+            pub fn div_rounded(self, denominator: $self, rounding: Rounding) -> Self {
+                let Self::Ok(value) = self else {
+                    return self;
+                };
+
+                if denominator == 0 {
+                    return Int::DivisionByZero;
+                }
+
+                let quotient = value / denominator;
+                let remainder = value % denominator;
+                // Can't overflow: a non-zero remainder means that the denominator is at least 2.
+                let round_up = match rounding {
+                    Rounding::Floor => false,
+                    Rounding::Ceil => remainder != 0,
+                    Rounding::HalfUp => remainder != 0 && remainder >= denominator - remainder,
+                };
+
+                Int::Ok(quotient + <$self>::from(round_up))
+            }
+        }
+    };
+}
+
+impl_int_div!(u32);
+impl_int_div!(u64);
+impl_int_div!(u128);
+
+impl_int!(u64, u128);
+impl_int!(u32, u64);
+
+impl<T> Int<T> {
+    pub fn into_result(self) -> Result<T, FeeError> {
+        match self {
+            Int::Ok(value) => Ok(value),
+            Int::Overflow => Err(FeeError::Overflow),
+            Int::DivisionByZero => Err(FeeError::DivisionByZero),
+        }
+    }
+
+    /// Converts the value to another integer type, which overflows if it doesn't fit.
+    pub fn narrow<U: TryFrom<T>>(self) -> Int<U> {
+        match self {
+            Int::Ok(value) => U::try_from(value).map_or(Int::Overflow, Int::Ok),
+            Int::Overflow => Int::Overflow,
+            Int::DivisionByZero => Int::DivisionByZero,
+        }
+    }
+}
+
+impl<T: Ord> Int<T> {
+    pub fn at_least(self, other: T) -> Self {
+        match self {
+            Int::Ok(value) => Int::Ok(value.max(other)),
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn unwrap<T>(int: Int<T>) -> T {
+        match int {
+            Int::Ok(value) => value,
+            Int::Overflow => panic!("Overflow"),
+            Int::DivisionByZero => panic!("DivisionByZero"),
+        }
+    }
+
+    #[test]
+    fn division_rounds_as_requested() {
+        let cases: [(u64, u64, [u64; 3]); 5] = [
+            (10, 5, [2, 2, 2]),
+            (11, 5, [2, 3, 2]),
+            (12, 5, [2, 3, 2]),
+            (13, 5, [2, 3, 3]),
+            (5, 2, [2, 3, 3]),
+        ];
+        for (value, denominator, [floor, ceil, half_up]) in cases {
+            assert_eq!(
+                unwrap(Int::Ok(value).div_rounded(denominator, Rounding::Floor)),
+                floor
+            );
+            assert_eq!(
+                unwrap(Int::Ok(value).div_rounded(denominator, Rounding::Ceil)),
+                ceil
+            );
+            assert_eq!(
+                unwrap(Int::Ok(value).div_rounded(denominator, Rounding::HalfUp)),
+                half_up
+            );
+        }
+
+        assert_eq!(
+            unwrap(Int::Ok(u64::MAX).div_rounded(2, Rounding::Ceil)),
+            1 << 63
+        );
+        assert_eq!(
+            unwrap(Int::Ok(7_u64).mul_div_rounded(3, 2, Rounding::Ceil)),
+            11
+        );
+        assert!(matches!(Int::Ok(u64::MAX).mul_div(3, 1), Int::Overflow));
+        assert!(matches!(
+            Int::Ok(1_u64).div_rounded(0, Rounding::Ceil),
+            Int::DivisionByZero
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn operations_match_checked_arithmetic(a: u64, b: u64) {
+            prop_assert_eq!((Int::Ok(a) + b).into_result().ok(), a.checked_add(b));
+            prop_assert_eq!((Int::Ok(a) - b).into_result().ok(), a.checked_sub(b));
+            prop_assert_eq!((Int::Ok(a) * b).into_result().ok(), a.checked_mul(b));
+            prop_assert_eq!((Int::Ok(a) / b).into_result().ok(), a.checked_div(b));
+            // Widening never overflows:
+            prop_assert!((Int::Ok(a) * u128::from(b)).into_result().is_ok());
+        }
+
+        #[test]
+        fn errors_propagate(a: u64, b: u64) {
+            let overflow = Int::Ok(u64::MAX) + 1_u64;
+            prop_assert_eq!(
+                ((overflow + a) * b / 0_u64 - a).into_result(),
+                Err(FeeError::Overflow)
+            );
+
+            let division_by_zero = Int::Ok(a) / 0_u64;
+            prop_assert_eq!(
+                (division_by_zero * b + u64::MAX - a).into_result(),
+                Err(FeeError::DivisionByZero)
+            );
+            prop_assert_eq!(
+                (Int::Ok(a) + (Int::Ok(b) / 0_u64)).into_result(),
+                Err(FeeError::DivisionByZero)
+            );
+            prop_assert_eq!(
+                (Int::Ok(a) * (Int::Ok(u64::MAX) + 1_u64)).into_result(),
+                Err(FeeError::Overflow)
+            );
+
+            let overflow = Int::Ok(u64::MAX) + 1_u64;
+            prop_assert_eq!(
+                overflow.div_rounded(0, Rounding::Ceil).into_result(),
+                Err(FeeError::Overflow)
+            );
+            prop_assert_eq!(
+                (Int::Ok(a) / 0_u64).mul_div(b, 1).into_result(),
+                Err(FeeError::DivisionByZero)
+            );
+            prop_assert_eq!(
+                (Int::Ok(u128::MAX) + 1_u128).narrow::<u64>().into_result(),
+                Err(FeeError::Overflow)
+            );
+        }
+
+        #[test]
+        fn rounding_brackets_the_exact_quotient(value: u128, denominator in 1..=u128::MAX) {
+            let floor = value / denominator;
+            let remainder = value % denominator;
+            let rounded = |rounding| Int::Ok(value).div_rounded(denominator, rounding).into_result();
+
+            prop_assert_eq!(rounded(Rounding::Floor), Ok(floor));
+            prop_assert_eq!(rounded(Rounding::Ceil), Ok(floor + u128::from(remainder != 0)));
+            prop_assert_eq!(
+                rounded(Rounding::HalfUp),
+                Ok(floor + u128::from(remainder != 0 && remainder >= denominator - remainder))
+            );
+        }
+
+        #[test]
+        fn mul_div_matches_wide_arithmetic(value: u64, numerator: u64, denominator: u64) {
+            let expected = if denominator == 0 {
+                Err(FeeError::DivisionByZero)
+            } else {
+                let product = u128::from(value) * u128::from(numerator);
+                u64::try_from(product.div_ceil(u128::from(denominator)))
+                    .map_err(|_| FeeError::Overflow)
+            };
+
+            prop_assert_eq!(
+                Int::Ok(value)
+                    .mul_div_rounded(numerator, denominator, Rounding::Ceil)
+                    .into_result(),
+                expected
+            );
+        }
+    }
+}
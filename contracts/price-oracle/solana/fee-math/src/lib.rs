@@ -0,0 +1,25 @@
+//! The fee formulas of the price oracle and cctpr, without any dependencies so that off-chain
+//! services can quote exactly what the programs charge.
+#![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+pub mod adjustment;
+pub mod corridor;
+pub mod int;
+pub mod prices;
+#[cfg(test)]
+mod vectors;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeError {
+    /// Overflow occurred during a calculation.
+    Overflow,
+
+    /// Division by zero occurred during a calculation.
+    DivisionByZero,
+
+    /// The fee depends on the prices of a chain that weren't provided.
+    MissingPrices,
+}
@@ -0,0 +1,278 @@
+use crate::int::{Int, Rounding};
+
+const SUI_MIN_TRANSACTION_COST_MIST: u128 = 2_000;
+
+const MICRO_GAS_TOKEN_PER_GAS_TOKEN: u128 = 1_000_000;
+const MWEI_PER_MICRO_ETH: u128 = 1_000_000;
+const MWEI_PER_ETH: u128 = 1_000_000_000_000;
+const MIST_PER_MICRO_SUI: u128 = 1_000;
+const MIST_PER_SUI: u128 = 1_000_000_000;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Fees are computed in 10⁻¹⁸usd and only rounded when converted to their final unit.
+pub const USD18_PER_MICRO_USD: u128 = 1_000_000_000_000;
+
+/// The highest supported [`ChainPrices::precision`].
+pub const MAX_PRICE_PRECISION: u8 = 9;
+
+/// The prices of a foreign chain, as registered in the price oracle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainPrices {
+    /// The gas price in μusd/Token.
+    pub gas_token_price: u64,
+
+    /// The prices specific to the platform of the chain.
+    pub platform: PlatformPrices,
+
+    /// How many extra decimals all the prices have, e.g. with a precision of 3
+    /// `gas_token_price` is in 10⁻⁹usd/Token and an EVM `gas_price` in Kwei/gas.
+    pub precision: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatformPrices {
+    Evm {
+        /// The gas price / base fee for the Ethereum transaction, in Mwei/gas.
+        gas_price: u32,
+
+        /// Regulates the cost of including L2 transactions in the Ethereum chain, in Mwei/byte.
+        price_per_tx_byte: u32,
+    },
+    Sui {
+        /// How much one Computation Unit costs in MIST (10⁻⁹SUI).
+        computation_unit_price: u32,
+
+        /// How much one stored byte costs in MIST (10⁻⁹SUI).
+        byte_price: u32,
+
+        /// A percentage of the storage cost that is rebated to the user.
+        rebate_ratio: u8,
+    },
+}
+
+/// What the target contract consumes on the foreign chain. Only the fields of the platform of the
+/// chain are used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TargetChainCosts {
+    /// The gas used by the target contract on the EVM chain.
+    pub evm_transaction_gas: u32,
+    /// The transaction size in bytes of the target contract on the EVM chain.
+    pub evm_transaction_size: u32,
+
+    /// The amount of computation units the target contract on the Sui chain uses.
+    /// Must be the ceiling from the corresponding bucket (see SUI cost model).
+    pub sui_computation_units: u32,
+    /// The amount of storage units the target contract on the Sui chain uses.
+    pub sui_stored_bytes: u32,
+    /// The amount of storage units the target contract on the Sui chain deletes after the transaction.
+    pub sui_deleted_bytes: u32,
+}
+
+impl ChainPrices {
+    /// The factor between the prices and their documented unit.
+    fn scale(&self) -> Option<u128> {
+        (self.precision <= MAX_PRICE_PRECISION).then(|| 10_u128.pow(self.precision.into()))
+    }
+
+    pub fn micro_gas_token_to_micro_usd(&self, micro_gas_token: u32) -> Int<u64> {
+        let Some(scale) = self.scale() else {
+            return Int::Overflow;
+        };
+
+        // μusd = µToken * μusd·10^precision/Token / (µToken/Token * 10^precision)
+        (Int::Ok(u128::from(self.gas_token_price)) * micro_gas_token)
+            .div_rounded(MICRO_GAS_TOKEN_PER_GAS_TOKEN * scale, Rounding::Ceil)
+            .narrow()
+    }
+
+    /// The total fee in μusd, rounded up.
+    ///
+    /// # Arguments
+    ///
+    /// - `costs`: what the target contract consumes on the chain.
+    /// - `flat_fee_micro_token`: any kind of flat fee (for example a dropoff), in µ-target-token.
+    /// - `flat_fee_micro_usd`: any kind of flat fee (for example the relaying fee), in µ-usd.
+    pub fn total_fee_micro_usd(
+        &self,
+        costs: &TargetChainCosts,
+        flat_fee_micro_token: u32,
+        flat_fee_micro_usd: u32,
+    ) -> Int<u64> {
+        self.total_fee_usd18(costs, flat_fee_micro_token, flat_fee_micro_usd)
+            .div_rounded(USD18_PER_MICRO_USD, Rounding::Ceil)
+            .narrow()
+    }
+
+    /// Same as [`Self::total_fee_micro_usd`], in 10⁻¹⁸usd. Rounds up so that the fee is never
+    /// undercharged.
+    pub fn total_fee_usd18(
+        &self,
+        costs: &TargetChainCosts,
+        flat_fee_micro_token: u32,
+        flat_fee_micro_usd: u32,
+    ) -> Int<u128> {
+        let Some(scale) = self.scale() else {
+            return Int::Overflow;
+        };
+        let flat_fee_micro_token = u128::from(flat_fee_micro_token);
+        let flat_fee_micro_usd = u128::from(flat_fee_micro_usd);
+        (match self.platform {
+            PlatformPrices::Evm {
+                gas_price,
+                price_per_tx_byte,
+            } => {
+                // Mwei·10^precision = gas * Mwei·10^precision/gas + bytes * Mwei·10^precision/byte
+                //   + µToken * Mwei/µToken * 10^precision
+                let total_fees_mwei = Int::Ok(u128::from(costs.evm_transaction_gas)) * gas_price
+                    + Int::Ok(u128::from(costs.evm_transaction_size)) * price_per_tx_byte
+                    + Int::Ok(flat_fee_micro_token) * MWEI_PER_MICRO_ETH * scale;
+
+                // usd18 = Mwei·10^precision * μusd·10^precision/Token * usd18/μusd
+                //   / (Mwei/Token * 10^(2 * precision))
+                (total_fees_mwei * self.gas_token_price * (USD18_PER_MICRO_USD / MWEI_PER_ETH))
+                    .div_rounded(scale * scale, Rounding::Ceil)
+            }
+            PlatformPrices::Sui {
+                computation_unit_price,
+                byte_price,
+                rebate_ratio,
+            } => {
+                let bytes_ratio = {
+                    let rebate =
+                        u64::from(costs.sui_deleted_bytes) * u64::from(rebate_ratio) / 100_u64;
+                    let stored_bytes = u64::from(costs.sui_stored_bytes);
+
+                    stored_bytes.saturating_sub(rebate)
+                };
+
+                // MIST·10^precision = CU * MIST·10^precision/CU
+                let computation_fee =
+                    Int::Ok(u128::from(costs.sui_computation_units)) * computation_unit_price;
+                // MIST·10^precision = bytes * MIST·10^precision/byte
+                let storage_fee = Int::Ok(u128::from(bytes_ratio)) * byte_price;
+
+                let total_fees_mist = computation_fee
+                    + storage_fee
+                    + Int::Ok(flat_fee_micro_token) * MIST_PER_MICRO_SUI * scale;
+
+                // usd18 = MIST·10^precision * μusd·10^precision/SUI * usd18/μusd
+                //   / (MIST/SUI * 10^(2 * precision))
+                (total_fees_mist.at_least(SUI_MIN_TRANSACTION_COST_MIST * scale)
+                    * self.gas_token_price
+                    * (USD18_PER_MICRO_USD / MIST_PER_SUI))
+                    .div_rounded(scale * scale, Rounding::Ceil)
+            }
+        }) + Int::Ok(flat_fee_micro_usd) * USD18_PER_MICRO_USD
+    }
+}
+
+/// Rounded up since it's used to charge fees.
+pub fn micro_usd_to_lamports(micro_usd: u64, sol_price_micro_usd: u64) -> Int<u64> {
+    // μusd * lamports/SOL / μusd/SOL
+    (Int::Ok(micro_usd) * LAMPORTS_PER_SOL).div_rounded(sol_price_micro_usd, Rounding::Ceil)
+}
+
+/// Rounded up like [`micro_usd_to_lamports`].
+pub fn usd18_to_lamports(usd18: Int<u128>, sol_price_micro_usd: u64) -> Int<u64> {
+    // usd18 * lamports/SOL / (μusd/SOL * usd18/μusd)
+    (usd18 * LAMPORTS_PER_SOL)
+        .div_rounded(
+            u128::from(sol_price_micro_usd) * USD18_PER_MICRO_USD,
+            Rounding::Ceil,
+        )
+        .narrow()
+}
+
+pub fn lamports_to_micro_usd(lamports: u64, sol_price_micro_usd: u64) -> Int<u64> {
+    // lamports * μusd/SOL / lamports/SOL
+    Int::Ok(lamports) * sol_price_micro_usd / LAMPORTS_PER_SOL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FeeError;
+    use proptest::prelude::*;
+
+    fn chain_prices() -> impl Strategy<Value = ChainPrices> {
+        let platform = prop_oneof![
+            (any::<u32>(), any::<u32>()).prop_map(|(gas_price, price_per_tx_byte)| {
+                PlatformPrices::Evm {
+                    gas_price,
+                    price_per_tx_byte,
+                }
+            }),
+            (any::<u32>(), any::<u32>(), any::<u8>()).prop_map(
+                |(computation_unit_price, byte_price, rebate_ratio)| PlatformPrices::Sui {
+                    computation_unit_price,
+                    byte_price,
+                    rebate_ratio,
+                }
+            ),
+        ];
+        (any::<u64>(), platform, any::<u8>()).prop_map(|(gas_token_price, platform, precision)| {
+            ChainPrices {
+                gas_token_price,
+                platform,
+                precision,
+            }
+        })
+    }
+
+    fn target_chain_costs() -> impl Strategy<Value = TargetChainCosts> {
+        any::<[u32; 5]>().prop_map(|costs| TargetChainCosts {
+            evm_transaction_gas: costs[0],
+            evm_transaction_size: costs[1],
+            sui_computation_units: costs[2],
+            sui_stored_bytes: costs[3],
+            sui_deleted_bytes: costs[4],
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn fees_never_panic(
+            prices in chain_prices(),
+            costs in target_chain_costs(),
+            flat_fee_micro_token: u32,
+            flat_fee_micro_usd: u32,
+            sol_price_micro_usd: u64,
+        ) {
+            let total_fee_usd18 = prices.total_fee_usd18(&costs, flat_fee_micro_token, flat_fee_micro_usd);
+            let total_fee_micro_usd =
+                prices.total_fee_micro_usd(&costs, flat_fee_micro_token, flat_fee_micro_usd);
+            let micro_usd = prices.micro_gas_token_to_micro_usd(flat_fee_micro_token);
+            let lamports = usd18_to_lamports(total_fee_usd18, sol_price_micro_usd);
+
+            if prices.precision > MAX_PRICE_PRECISION {
+                prop_assert_eq!(total_fee_micro_usd.into_result(), Err(FeeError::Overflow));
+                prop_assert_eq!(micro_usd.into_result(), Err(FeeError::Overflow));
+            } else if let Ok(total_fee_micro_usd) = total_fee_micro_usd.into_result() {
+                prop_assert!(total_fee_micro_usd >= u64::from(flat_fee_micro_usd));
+            }
+            if sol_price_micro_usd == 0 {
+                prop_assert!(lamports.into_result().is_err());
+            }
+        }
+
+        #[test]
+        fn fees_grow_with_the_gas_token_price(
+            prices in chain_prices(),
+            costs in target_chain_costs(),
+            flat_fee_micro_token: u32,
+            gas_token_price: u64,
+        ) {
+            let (cheaper, pricier) = (
+                ChainPrices { gas_token_price: gas_token_price.min(prices.gas_token_price), ..prices },
+                ChainPrices { gas_token_price: gas_token_price.max(prices.gas_token_price), ..prices },
+            );
+            if let (Ok(cheaper), Ok(pricier)) = (
+                cheaper.total_fee_micro_usd(&costs, flat_fee_micro_token, 0).into_result(),
+                pricier.total_fee_micro_usd(&costs, flat_fee_micro_token, 0).into_result(),
+            ) {
+                prop_assert!(cheaper <= pricier);
+            }
+        }
+    }
+}
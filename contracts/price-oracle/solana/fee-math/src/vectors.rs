@@ -0,0 +1,380 @@
+//! The relay fee scenarios in `vectors/relay_fees.json`, which pin down the Solana relay fees.
+//!
+//! Nothing outside of this crate consumes the file yet. It follows the EVM `_quoteInUsdc` in that
+//! the fees of all legs are summed in 18-decimal USD, but the sum is rounded up to μusd once where
+//! the EVM contract truncates it, so its fees can be up to one μusd above the EVM quotes.
+//!
+//! The file is generated from [`scenarios`]; run `UPDATE_FEE_VECTORS=1 cargo test -p fee-math`
+//! to regenerate it after changing them or the fee formulas.
+
+use crate::{
+    adjustment::FeeAdjustment,
+    corridor::{relay_fee_micro_usd, Corridor, RelayFeeParams},
+    prices::{micro_usd_to_lamports, ChainPrices, PlatformPrices},
+};
+use serde_json::{json, Value};
+use std::{format, fs, string::String, vec::Vec};
+
+const VECTORS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/vectors/relay_fees.json");
+
+/// Integers above 2^53 can't be read exactly by JavaScript.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+const SOL_PRICE_MICRO_USD: u64 = 150_000_000;
+
+const AVALANCHE_PRICES: ChainPrices = ChainPrices {
+    gas_token_price: 25_000_000, // 25 USD per AVAX
+    platform: PlatformPrices::Evm {
+        gas_price: 25_000, // 25 gwei
+        price_per_tx_byte: 0,
+    },
+    precision: 0,
+};
+
+const DESTINATION_PRICES: [(&str, ChainPrices); 3] = [
+    (
+        "ethereum",
+        ChainPrices {
+            gas_token_price: 2_500_000_000, // 2500 USD per ETH
+            platform: PlatformPrices::Evm {
+                gas_price: 2_000, // 2 gwei
+                price_per_tx_byte: 0,
+            },
+            precision: 0,
+        },
+    ),
+    (
+        "cheap-l2",
+        ChainPrices {
+            gas_token_price: 2_500_000_000_000, // 2500 USD per ETH, in 10⁻⁹usd
+            platform: PlatformPrices::Evm {
+                gas_price: 10_123,        // 0.010123 gwei, in Kwei
+                price_per_tx_byte: 1_500, // 1.5 Mwei/byte, in Kwei
+            },
+            precision: 3,
+        },
+    ),
+    (
+        "sui",
+        ChainPrices {
+            gas_token_price: 3_000_000, // 3 USD per SUI
+            platform: PlatformPrices::Sui {
+                computation_unit_price: 750,
+                byte_price: 7_600,
+                rebate_ratio: 99,
+            },
+            precision: 0,
+        },
+    ),
+];
+
+const CORRIDORS: [Corridor; 3] = [Corridor::V1, Corridor::V2Direct, Corridor::AvaxHop];
+
+const GAS_DROPOFFS_MICRO_GAS_TOKEN: [u32; 2] = [0, 12_345];
+
+/// Pairs of corridor and gas dropoff adjustments.
+const ADJUSTMENTS: [(FeeAdjustment, FeeAdjustment); 3] = [
+    (FeeAdjustment::IDENTITY, FeeAdjustment::IDENTITY),
+    (
+        FeeAdjustment {
+            absolute_usd: 50_000,
+            relative_percent_bps: 11_000,
+        },
+        FeeAdjustment {
+            absolute_usd: -1_000,
+            relative_percent_bps: 10_333,
+        },
+    ),
+    // Purely absolute, so no prices are needed for the destination:
+    (
+        FeeAdjustment {
+            absolute_usd: 200_000,
+            relative_percent_bps: 0,
+        },
+        FeeAdjustment {
+            absolute_usd: 30_000,
+            relative_percent_bps: 0,
+        },
+    ),
+];
+
+const DISCOUNTS: [Option<FeeAdjustment>; 2] = [
+    None,
+    Some(FeeAdjustment {
+        absolute_usd: -10_000,
+        relative_percent_bps: 9_000,
+    }),
+];
+
+fn corridor_name(corridor: Corridor) -> &'static str {
+    match corridor {
+        Corridor::V1 => "V1",
+        Corridor::V2Direct => "V2Direct",
+        Corridor::AvaxHop => "AvaxHop",
+    }
+}
+
+fn corridor_from_json(value: &Value) -> Corridor {
+    *CORRIDORS
+        .iter()
+        .find(|corridor| value == corridor_name(**corridor))
+        .expect("Unknown corridor")
+}
+
+fn prices_to_json(prices: Option<&ChainPrices>) -> Value {
+    let Some(prices) = prices else {
+        return Value::Null;
+    };
+
+    match prices.platform {
+        PlatformPrices::Evm {
+            gas_price,
+            price_per_tx_byte,
+        } => json!({
+            "platform": "Evm",
+            "gasTokenPrice": prices.gas_token_price,
+            "gasPrice": gas_price,
+            "pricePerTxByte": price_per_tx_byte,
+            "precision": prices.precision,
+        }),
+        PlatformPrices::Sui {
+            computation_unit_price,
+            byte_price,
+            rebate_ratio,
+        } => json!({
+            "platform": "Sui",
+            "gasTokenPrice": prices.gas_token_price,
+            "computationUnitPrice": computation_unit_price,
+            "bytePrice": byte_price,
+            "rebateRatio": rebate_ratio,
+            "precision": prices.precision,
+        }),
+    }
+}
+
+fn uint<T: TryFrom<u64>>(value: &Value, field: &str) -> T {
+    let value = value[field].as_u64().expect("Missing integer");
+    T::try_from(value).unwrap_or_else(|_| panic!("{field} is out of range"))
+}
+
+fn prices_from_json(value: &Value) -> Option<ChainPrices> {
+    if value.is_null() {
+        return None;
+    }
+
+    let platform = match value["platform"].as_str() {
+        Some("Evm") => PlatformPrices::Evm {
+            gas_price: uint(value, "gasPrice"),
+            price_per_tx_byte: uint(value, "pricePerTxByte"),
+        },
+        Some("Sui") => PlatformPrices::Sui {
+            computation_unit_price: uint(value, "computationUnitPrice"),
+            byte_price: uint(value, "bytePrice"),
+            rebate_ratio: uint(value, "rebateRatio"),
+        },
+        _ => panic!("Unknown platform"),
+    };
+
+    Some(ChainPrices {
+        gas_token_price: uint(value, "gasTokenPrice"),
+        platform,
+        precision: uint(value, "precision"),
+    })
+}
+
+fn adjustment_to_json(adjustment: Option<FeeAdjustment>) -> Value {
+    adjustment.map_or(Value::Null, |adjustment| {
+        json!({
+            "absoluteUsd": adjustment.absolute_usd,
+            "relativePercentBps": adjustment.relative_percent_bps,
+        })
+    })
+}
+
+fn adjustment_from_json(value: &Value) -> Option<FeeAdjustment> {
+    if value.is_null() {
+        return None;
+    }
+
+    let absolute_usd = value["absoluteUsd"].as_i64().expect("Missing integer");
+    Some(FeeAdjustment {
+        absolute_usd: absolute_usd
+            .try_into()
+            .expect("absoluteUsd is out of range"),
+        relative_percent_bps: uint(value, "relativePercentBps"),
+    })
+}
+
+/// What the programs charge in the given scenario, or why they reject it.
+fn expected(params: &RelayFeeParams, sol_price_micro_usd: u64) -> Value {
+    match relay_fee_micro_usd(params) {
+        Ok(relay_fee_micro_usd) => {
+            let relay_fee_lamports =
+                micro_usd_to_lamports(relay_fee_micro_usd, sol_price_micro_usd)
+                    .into_result()
+                    .unwrap();
+            assert!(relay_fee_micro_usd <= MAX_SAFE_INTEGER);
+            assert!(relay_fee_lamports <= MAX_SAFE_INTEGER);
+            json!({
+                "relayFeeMicroUsd": relay_fee_micro_usd,
+                "relayFeeLamports": relay_fee_lamports,
+            })
+        }
+        Err(error) => json!({ "error": format!("{error:?}") }),
+    }
+}
+
+fn scenario_to_json(name: String, params: &RelayFeeParams) -> Value {
+    json!({
+        "name": name,
+        "corridor": corridor_name(params.corridor),
+        "gasDropoffMicroGasToken": params.gas_dropoff_micro_gas_token,
+        "avalanchePrices": prices_to_json(params.avalanche_prices),
+        "destinationPrices": prices_to_json(params.destination_prices),
+        "corridorAdjustment": adjustment_to_json(Some(params.corridor_adjustment)),
+        "gasDropoffAdjustment": adjustment_to_json(Some(params.gas_dropoff_adjustment)),
+        "corridorDiscount": adjustment_to_json(params.corridor_discount),
+        "gasDropoffDiscount": adjustment_to_json(params.gas_dropoff_discount),
+        "solPriceMicroUsd": SOL_PRICE_MICRO_USD,
+        "expected": expected(params, SOL_PRICE_MICRO_USD),
+    })
+}
+
+fn scenarios() -> Vec<Value> {
+    let mut scenarios = Vec::new();
+    for (destination, destination_prices) in &DESTINATION_PRICES {
+        for corridor in CORRIDORS {
+            for gas_dropoff_micro_gas_token in GAS_DROPOFFS_MICRO_GAS_TOKEN {
+                for (adjustment_index, (corridor_adjustment, gas_dropoff_adjustment)) in
+                    ADJUSTMENTS.into_iter().enumerate()
+                {
+                    for (discount_index, discount) in DISCOUNTS.into_iter().enumerate() {
+                        let params = RelayFeeParams {
+                            corridor,
+                            gas_dropoff_micro_gas_token,
+                            avalanche_prices: Some(&AVALANCHE_PRICES),
+                            destination_prices: Some(destination_prices),
+                            corridor_adjustment,
+                            gas_dropoff_adjustment,
+                            corridor_discount: discount,
+                            gas_dropoff_discount: discount,
+                        };
+                        let name = format!(
+                            "{destination}/{}/dropoff-{gas_dropoff_micro_gas_token}\
+                             /adjustment-{adjustment_index}/discount-{discount_index}",
+                            corridor_name(corridor),
+                        );
+                        scenarios.push(scenario_to_json(name, &params));
+                    }
+                }
+            }
+        }
+    }
+
+    // The quote is rejected if prices that it depends on weren't provided:
+    let (_, ethereum_prices) = &DESTINATION_PRICES[0];
+    let (absolute_adjustment, _) = ADJUSTMENTS[2];
+    let missing_prices = [
+        (
+            "missing-avalanche-prices",
+            Corridor::AvaxHop,
+            0,
+            FeeAdjustment::IDENTITY,
+            None,
+            Some(ethereum_prices),
+        ),
+        (
+            "missing-destination-prices",
+            Corridor::V1,
+            0,
+            FeeAdjustment::IDENTITY,
+            Some(&AVALANCHE_PRICES),
+            None,
+        ),
+        (
+            "missing-gas-dropoff-prices",
+            Corridor::V1,
+            12_345,
+            absolute_adjustment,
+            Some(&AVALANCHE_PRICES),
+            None,
+        ),
+    ];
+    for (
+        name,
+        corridor,
+        gas_dropoff_micro_gas_token,
+        corridor_adjustment,
+        avalanche_prices,
+        destination_prices,
+    ) in missing_prices
+    {
+        let params = RelayFeeParams {
+            corridor,
+            gas_dropoff_micro_gas_token,
+            avalanche_prices,
+            destination_prices,
+            corridor_adjustment,
+            gas_dropoff_adjustment: FeeAdjustment::IDENTITY,
+            corridor_discount: None,
+            gas_dropoff_discount: None,
+        };
+        scenarios.push(scenario_to_json(name.into(), &params));
+    }
+
+    scenarios
+}
+
+fn corpus() -> String {
+    let corpus = json!({
+        "description": "Relay fees of on-chain quotes, generated by the fee-math crate. Prices use \
+            the units of the price oracle, fees and adjustments are in μusd.",
+        "scenarios": scenarios(),
+    });
+    format!("{}\n", serde_json::to_string_pretty(&corpus).unwrap())
+}
+
+#[test]
+fn corpus_is_up_to_date() {
+    let generated = corpus();
+    if std::env::var_os("UPDATE_FEE_VECTORS").is_some() {
+        fs::write(VECTORS_PATH, &generated).unwrap();
+    }
+
+    let stored = fs::read_to_string(VECTORS_PATH).unwrap();
+    assert!(
+        stored == generated,
+        "{VECTORS_PATH} is outdated, run `UPDATE_FEE_VECTORS=1 cargo test -p fee-math`"
+    );
+}
+
+/// Reads the corpus the same way the other implementations do.
+#[test]
+fn corpus_matches_implementation() {
+    let corpus: Value = serde_json::from_str(&fs::read_to_string(VECTORS_PATH).unwrap()).unwrap();
+    let scenarios = corpus["scenarios"].as_array().unwrap();
+    assert!(!scenarios.is_empty());
+
+    for scenario in scenarios {
+        let avalanche_prices = prices_from_json(&scenario["avalanchePrices"]);
+        let destination_prices = prices_from_json(&scenario["destinationPrices"]);
+        let params = RelayFeeParams {
+            corridor: corridor_from_json(&scenario["corridor"]),
+            gas_dropoff_micro_gas_token: uint(scenario, "gasDropoffMicroGasToken"),
+            avalanche_prices: avalanche_prices.as_ref(),
+            destination_prices: destination_prices.as_ref(),
+            corridor_adjustment: adjustment_from_json(&scenario["corridorAdjustment"]).unwrap(),
+            gas_dropoff_adjustment: adjustment_from_json(&scenario["gasDropoffAdjustment"])
+                .unwrap(),
+            corridor_discount: adjustment_from_json(&scenario["corridorDiscount"]),
+            gas_dropoff_discount: adjustment_from_json(&scenario["gasDropoffDiscount"]),
+        };
+
+        assert_eq!(
+            expected(&params, uint(scenario, "solPriceMicroUsd")),
+            scenario["expected"],
+            "{}",
+            scenario["name"]
+        );
+    }
+}
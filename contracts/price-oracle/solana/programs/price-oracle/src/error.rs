@@ -1,4 +1,6 @@
+use crate::state::StaleProposal;
 use anchor_lang::prelude::error_code;
+use fee_math::FeeError;
 
 #[error_code]
 pub(crate) enum PriceOracleError {
@@ -35,10 +37,63 @@ pub(crate) enum PriceOracleError {
     #[msg("AssistantDeletionOnly")]
     AssistantDeletionOnly,
 
+    /// The owner badge can't be given an expiration.
+    #[msg("OwnerBadgeCannotExpire")]
+    OwnerBadgeCannotExpire,
+
+    /// A badge expiration must be in the future, or zero for a badge that never expires.
+    #[msg("InvalidExpiration")]
+    InvalidExpiration,
+
+    /// Only expired badges can be closed permissionlessly.
+    #[msg("BadgeNotExpired")]
+    BadgeNotExpired,
+
+    /// The same badge was passed twice.
+    #[msg("DuplicateBadge")]
+    DuplicateBadge,
+
+    /// The upgrade can't be executed before the end of its delay.
+    #[msg("UpgradeTimelockNotElapsed")]
+    UpgradeTimelockNotElapsed,
+
+    /// The buffer doesn't hold the proposed program anymore.
+    #[msg("BufferHashMismatch")]
+    BufferHashMismatch,
+
+    /// A multisig needs between 1 and `MAX_MULTISIG_SIGNERS` distinct signers, and a threshold
+    /// between 1 and the number of signers.
+    #[msg("InvalidMultisig")]
+    InvalidMultisig,
+
+    /// Only the signers of the multisig are permitted.
+    #[msg("MultisigSignerOnly")]
+    MultisigSignerOnly,
+
+    /// The signer already approved the proposal.
+    #[msg("AlreadyApproved")]
+    AlreadyApproved,
+
+    /// The proposal needs more approvals before it can be executed.
+    #[msg("NotEnoughApprovals")]
+    NotEnoughApprovals,
+
     /// The provided chain ID does not match the called method, or the Solana chain ID was provided.
     #[msg("InvalidChainId")]
     InvalidChainId,
 
+    /// Prices support at most `MAX_PRICE_PRECISION` extra decimals.
+    #[msg("InvalidPrecision")]
+    InvalidPrecision,
+
+    /// The assistant's scope doesn't include the prices it tried to update.
+    #[msg("OutOfScope")]
+    OutOfScope,
+
+    /// An assistant can be scoped to at most `MAX_SCOPED_CHAIN_IDS` individual chains.
+    #[msg("TooManyScopedChains")]
+    TooManyScopedChains,
+
     /// Overflow occurred during a calculation.
     #[msg("Overflow")]
     Overflow,
@@ -46,4 +101,35 @@ pub(crate) enum PriceOracleError {
     /// Division by zero occurred during a calculation.
     #[msg("DivisionByZero")]
     DivisionByZero,
+
+    /// The prices account was allocated but no prices were registered in it.
+    #[msg("UninitializedPrices")]
+    UninitializedPrices,
+
+    /// The signers or the threshold of the multisig changed since the proposal was made.
+    #[msg("ProposalOutdated")]
+    ProposalOutdated,
+
+    /// The lifetime of the multisig proposal elapsed.
+    #[msg("ProposalExpired")]
+    ProposalExpired,
+}
+
+impl From<StaleProposal> for PriceOracleError {
+    fn from(stale: StaleProposal) -> Self {
+        match stale {
+            StaleProposal::Outdated => PriceOracleError::ProposalOutdated,
+            StaleProposal::Expired => PriceOracleError::ProposalExpired,
+        }
+    }
+}
+
+impl From<FeeError> for PriceOracleError {
+    fn from(error: FeeError) -> Self {
+        match error {
+            FeeError::Overflow => PriceOracleError::Overflow,
+            FeeError::DivisionByZero => PriceOracleError::DivisionByZero,
+            FeeError::MissingPrices => PriceOracleError::UninitializedPrices,
+        }
+    }
 }
@@ -0,0 +1,254 @@
+//! Events emitted via CPI (see `emit_cpi!`) so that indexers can reconstruct the price history and
+//! the set of authorized accounts without polling accounts.
+//!
+//! Each price event carries the complete prices of its chain before and after the instruction,
+//! including the ones that weren't touched by it. Registrations and resets report all old prices
+//! as 0.
+
+use crate::{
+    error::PriceOracleError,
+    state::{PricesState, PricesStatePlatform},
+};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct SolPriceUpdated {
+    pub signer: Pubkey,
+    pub slot: u64,
+    pub old_sol_price: u64,
+    pub new_sol_price: u64,
+}
+
+#[event]
+pub struct EvmPricesUpdated {
+    pub chain_id: u16,
+    pub signer: Pubkey,
+    pub slot: u64,
+    pub old_gas_token_price: u64,
+    pub new_gas_token_price: u64,
+    pub old_gas_price: u32,
+    pub new_gas_price: u32,
+    pub old_price_per_tx_byte: u32,
+    pub new_price_per_tx_byte: u32,
+    /// The extra decimals of all the prices, see [`PricesState::precision`].
+    pub precision: u8,
+}
+
+#[event]
+pub struct SuiPricesUpdated {
+    pub chain_id: u16,
+    pub signer: Pubkey,
+    pub slot: u64,
+    pub old_gas_token_price: u64,
+    pub new_gas_token_price: u64,
+    pub old_computation_unit_price: u32,
+    pub new_computation_unit_price: u32,
+    pub old_byte_price: u32,
+    pub new_byte_price: u32,
+    pub old_rebate_ratio: u8,
+    pub new_rebate_ratio: u8,
+    /// The extra decimals of all the prices, see [`PricesState::precision`].
+    pub precision: u8,
+}
+
+/// The prices of the chain were rescaled to the new precision.
+#[event]
+pub struct PricesPrecisionUpdated {
+    pub chain_id: u16,
+    pub old_precision: u8,
+    pub new_precision: u8,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PricesDeregistered {
+    pub chain_id: u16,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+/// An unscoped assistant (`is_scoped == false`) may update all prices.
+#[event]
+pub struct AssistantScopeUpdated {
+    pub assistant: Pubkey,
+    pub is_scoped: bool,
+    pub sol_price: bool,
+    pub platforms: u8,
+    pub chain_ids: Vec<u16>,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthBadgeAdded {
+    pub address: Pubkey,
+    pub is_admin: bool,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthBadgeRemoved {
+    pub address: Pubkey,
+    pub is_admin: bool,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthBadgeRenewed {
+    pub address: Pubkey,
+    pub is_admin: bool,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UpgradeDelayUpdated {
+    pub old_delay: u32,
+    pub new_delay: u32,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UpgradeProposed {
+    pub buffer: Pubkey,
+    pub buffer_hash: [u8; 32],
+    pub executable_at: i64,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UpgradeExecuted {
+    pub buffer: Pubkey,
+    pub buffer_hash: [u8; 32],
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UpgradeCancelled {
+    pub buffer: Pubkey,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+/// Emitted when a multisig is created or its signers are changed.
+#[event]
+pub struct MultisigUpdated {
+    pub multisig: Pubkey,
+    pub authority: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MultisigProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub expires_at: i64,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MultisigProposalApproved {
+    pub proposal: Pubkey,
+    pub approval_count: u8,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MultisigProposalExecuted {
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MultisigProposalCancelled {
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+impl EvmPricesUpdated {
+    /// Fails if `new` aren't EVM prices or if `old` are initialized but aren't EVM prices.
+    pub fn new(signer: Pubkey, old: &PricesState, new: &PricesState) -> Result<Self> {
+        let (old_gas_price, old_price_per_tx_byte) = evm_prices(&old.prices)?.unwrap_or_default();
+        let (new_gas_price, new_price_per_tx_byte) =
+            evm_prices(&new.prices)?.ok_or(PriceOracleError::InvalidChainId)?;
+
+        Ok(Self {
+            chain_id: new.chain_id,
+            signer,
+            slot: Clock::get()?.slot,
+            old_gas_token_price: old.gas_token_price,
+            new_gas_token_price: new.gas_token_price,
+            old_gas_price,
+            new_gas_price,
+            old_price_per_tx_byte,
+            new_price_per_tx_byte,
+            precision: new.precision,
+        })
+    }
+}
+
+impl SuiPricesUpdated {
+    /// Fails if `new` aren't Sui prices or if `old` are initialized but aren't Sui prices.
+    pub fn new(signer: Pubkey, old: &PricesState, new: &PricesState) -> Result<Self> {
+        let (old_computation_unit_price, old_byte_price, old_rebate_ratio) =
+            sui_prices(&old.prices)?.unwrap_or_default();
+        let (new_computation_unit_price, new_byte_price, new_rebate_ratio) =
+            sui_prices(&new.prices)?.ok_or(PriceOracleError::InvalidChainId)?;
+
+        Ok(Self {
+            chain_id: new.chain_id,
+            signer,
+            slot: Clock::get()?.slot,
+            old_gas_token_price: old.gas_token_price,
+            new_gas_token_price: new.gas_token_price,
+            old_computation_unit_price,
+            new_computation_unit_price,
+            old_byte_price,
+            new_byte_price,
+            old_rebate_ratio,
+            new_rebate_ratio,
+            precision: new.precision,
+        })
+    }
+}
+
+/// `None` if the prices are uninitialized, fails for prices of another platform.
+fn evm_prices(prices: &PricesStatePlatform) -> Result<Option<(u32, u32)>> {
+    match *prices {
+        PricesStatePlatform::Uninitialized(_) => Ok(None),
+        PricesStatePlatform::Evm {
+            gas_price,
+            price_per_tx_byte,
+        } => Ok(Some((gas_price, price_per_tx_byte))),
+        _ => Err(PriceOracleError::InvalidChainId.into()),
+    }
+}
+
+/// `None` if the prices are uninitialized, fails for prices of another platform.
+fn sui_prices(prices: &PricesStatePlatform) -> Result<Option<(u32, u32, u8)>> {
+    match *prices {
+        PricesStatePlatform::Uninitialized(_) => Ok(None),
+        PricesStatePlatform::Sui {
+            computation_unit_price,
+            byte_price,
+            rebate_ratio,
+        } => Ok(Some((computation_unit_price, byte_price, rebate_ratio))),
+        _ => Err(PriceOracleError::InvalidChainId.into()),
+    }
+}
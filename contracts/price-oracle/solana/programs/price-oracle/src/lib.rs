@@ -1,4 +1,5 @@
 pub mod error;
+pub mod events;
 pub mod id;
 pub mod int;
 pub mod processor;
@@ -9,6 +10,7 @@ pub use id::ID;
 
 use anchor_lang::prelude::*;
 use processor::*;
+use state::ProposedAccountMeta;
 
 #[constant]
 pub const SEED_PREFIX_UPGRADE_LOCK: &[u8] = b"upgrade_lock";
@@ -105,6 +107,180 @@ pub mod solana_price_oracle {
         processor::remove_assistant_role(ctx)
     }
 
+    /// Sets the Unix timestamp after which an admin or assistant badge no longer grants its
+    /// role, or zero for a badge that never expires. The owner badge can't expire.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin (assistant badges only)
+    pub fn renew_auth_badge(ctx: Context<RenewAuthBadge>, expires_at: i64) -> Result<()> {
+        processor::renew_auth_badge(ctx, expires_at)
+    }
+
+    /// Closes an expired badge. The rent goes back to whoever paid for the badge.
+    ///
+    /// # Authorized
+    ///
+    /// - Anyone
+    pub fn close_expired_auth_badge(ctx: Context<CloseExpiredAuthBadge>) -> Result<()> {
+        processor::close_expired_auth_badge(ctx)
+    }
+
+    /// Creates the registry listing all badge holders, for programs initialized before it
+    /// existed. All the existing badges must be passed in `remaining_accounts`.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    pub fn init_role_registry<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitRoleRegistry<'info>>,
+    ) -> Result<()> {
+        processor::init_role_registry(ctx)
+    }
+
+    /// Restricts an assistant to the SOL price (if `sol_price`), all chains of the given
+    /// `platforms` (a bit set, see `Platform::mask`) and the given individual chains.
+    /// Replaces its previous scope, if any.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn set_assistant_scope(
+        ctx: Context<SetAssistantScope>,
+        assistant: Pubkey,
+        sol_price: bool,
+        platforms: u8,
+        chain_ids: Vec<u16>,
+    ) -> Result<()> {
+        processor::set_assistant_scope(ctx, assistant, sol_price, platforms, chain_ids)
+    }
+
+    /// Lifts the restrictions of a scoped assistant.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn remove_assistant_scope(ctx: Context<RemoveAssistantScope>) -> Result<()> {
+        processor::remove_assistant_scope(ctx)
+    }
+
+    /* Multisig */
+
+    /// Creates an M-of-N multisig. Its authority (see `MultisigState::authority`) can then
+    /// become the owner via an owner transfer confirmed through a multisig proposal.
+    ///
+    /// # Authorized
+    ///
+    /// - Anyone
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        create_key: Pubkey,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        processor::create_multisig(ctx, create_key, signers, threshold)
+    }
+
+    /// Replaces the signers and the threshold of a multisig, which invalidates its pending
+    /// proposals.
+    ///
+    /// # Authorized
+    ///
+    /// - The multisig authority, i.e. through an approved proposal
+    pub fn update_multisig(
+        ctx: Context<UpdateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        processor::update_multisig(ctx, signers, threshold)
+    }
+
+    /// Proposes an instruction of this program, to be invoked with the multisig authority as
+    /// signer. The proposer approves it right away. It can be approved and executed for a week.
+    ///
+    /// # Authorized
+    ///
+    /// - Multisig signer
+    pub fn propose_multisig_instruction(
+        ctx: Context<ProposeMultisigInstruction>,
+        accounts: Vec<ProposedAccountMeta>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        processor::propose_multisig_instruction(ctx, accounts, data)
+    }
+
+    /// # Authorized
+    ///
+    /// - Multisig signer
+    pub fn approve_multisig_proposal(ctx: Context<ApproveMultisigProposal>) -> Result<()> {
+        processor::approve_multisig_proposal(ctx)
+    }
+
+    /// Invokes the proposed instruction once enough signers approved it. The accounts of the
+    /// instruction must be passed in `remaining_accounts`.
+    ///
+    /// # Authorized
+    ///
+    /// - Anyone
+    pub fn execute_multisig_proposal<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteMultisigProposal<'info>>,
+    ) -> Result<()> {
+        processor::execute_multisig_proposal(ctx)
+    }
+
+    /// # Authorized
+    ///
+    /// - Multisig signer
+    /// - The multisig authority, i.e. through an approved proposal
+    /// - Anyone, once the proposal expired or the multisig changed
+    pub fn cancel_multisig_proposal(ctx: Context<CancelMultisigProposal>) -> Result<()> {
+        processor::cancel_multisig_proposal(ctx)
+    }
+
+    /* Upgrades */
+
+    /// Sets how long, in seconds, an upgrade must wait between its proposal and its execution.
+    /// Only applies to the upgrades proposed afterwards.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    pub fn set_upgrade_delay(ctx: Context<SetUpgradeDelay>, delay: u32) -> Result<()> {
+        processor::set_upgrade_delay(ctx, delay)
+    }
+
+    /// Proposes to upgrade the program to the content of a buffer, whose hash is recorded.
+    /// The authority of the buffer is passed on to the upgrade lock until the upgrade is executed
+    /// or cancelled, the one of the program stays with it for good.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    pub fn propose_upgrade(ctx: Context<ProposeUpgrade>) -> Result<()> {
+        processor::propose_upgrade(ctx)
+    }
+
+    /// Upgrades the program once the delay of the proposal has elapsed.
+    ///
+    /// # Authorized
+    ///
+    /// - Anyone
+    pub fn execute_upgrade(ctx: Context<ExecuteUpgrade>) -> Result<()> {
+        processor::execute_upgrade(ctx)
+    }
+
+    /// Cancels the pending upgrade, and gives the authority of the buffer back to the owner.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    pub fn cancel_upgrade(ctx: Context<CancelUpgrade>) -> Result<()> {
+        processor::cancel_upgrade(ctx)
+    }
+
     /* Prices */
 
     /// Update the Solana price, in μusd/SOL.
@@ -203,6 +379,91 @@ pub mod solana_price_oracle {
         processor::update_sui_prices(ctx, computation_unit_price, gas_token_price)
     }
 
+    /// Starts recording the prices of an already registered chain in a ring buffer, from which
+    /// smoothed prices can be derived (see `PriceHistoryState::twap`). All subsequent updates are
+    /// recorded in it.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    /// - Assistant
+    pub fn init_price_history(ctx: Context<InitPriceHistory>) -> Result<()> {
+        processor::init_price_history(ctx)
+    }
+
+    /// Closes the prices of a chain (and its price history, if any), e.g. if it was registered
+    /// by mistake. The rent goes to the signer.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn deregister_prices(ctx: Context<DeregisterPrices>) -> Result<()> {
+        processor::deregister_prices(ctx)
+    }
+
+    /// Overwrites the prices of an already registered EVM chain, whatever platform they were
+    /// registered for. The price history, if any, is restarted.
+    ///
+    /// The emitted event reports the overwritten prices as if they were EVM prices.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn reset_evm_prices(
+        ctx: Context<ResetPrices>,
+        chain_id: u16,
+        gas_token_price: u64,
+        gas_price: u32,
+        price_per_tx_byte: u32,
+    ) -> Result<()> {
+        processor::reset_evm_prices(ctx, chain_id, gas_token_price, gas_price, price_per_tx_byte)
+    }
+
+    /// Overwrites the prices of an already registered Sui chain, whatever platform they were
+    /// registered for. The price history, if any, is restarted.
+    ///
+    /// The emitted event reports the overwritten prices as if they were Sui prices.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn reset_sui_prices(
+        ctx: Context<ResetPrices>,
+        chain_id: u16,
+        gas_token_price: u64,
+        computation_unit_price: u32,
+        byte_price: u32,
+        rebate_ratio: u8,
+    ) -> Result<()> {
+        processor::reset_sui_prices(
+            ctx,
+            chain_id,
+            gas_token_price,
+            computation_unit_price,
+            byte_price,
+            rebate_ratio,
+        )
+    }
+
+    /// Rescales all the prices of a chain to the given number of extra decimals, e.g. for chains
+    /// whose gas is too cheap to be priced in whole Mwei or MIST. The price history, if any, is
+    /// restarted. The updates that follow must be expressed in the new precision.
+    ///
+    /// # Authorized
+    ///
+    /// - Owner
+    /// - Admin
+    pub fn update_prices_precision(
+        ctx: Context<UpdatePricesPrecision>,
+        precision: u8,
+    ) -> Result<()> {
+        processor::update_prices_precision(ctx, precision)
+    }
+
     pub fn update_sui_byte_price(ctx: Context<UpdatePrices>, byte_price: u32) -> Result<()> {
         processor::update_sui_byte_price(ctx, byte_price)
     }
@@ -4,14 +4,29 @@ pub use admins::*;
 pub mod assistants;
 pub use assistants::*;
 
+pub mod badges;
+pub use badges::*;
+
 pub mod initialize;
 pub use initialize::*;
 
 pub mod prices;
 pub use prices::*;
 
+pub mod multisig;
+pub use multisig::*;
+
 pub mod owner;
 pub use owner::*;
 
 pub mod sol_price;
 pub use sol_price::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod upgrade;
+pub use upgrade::*;
+
+pub mod scopes;
+pub use scopes::*;
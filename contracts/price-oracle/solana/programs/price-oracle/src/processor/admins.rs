@@ -1,9 +1,11 @@
 use crate::{
     error::PriceOracleError,
-    state::{AuthBadgeState, PriceOracleConfigState},
+    events::{AuthBadgeAdded, AuthBadgeRemoved},
+    state::{AuthBadgeState, PriceOracleConfigState, RoleRegistryState},
 };
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(
     new_admin: Pubkey,
@@ -27,6 +29,17 @@ pub struct AddAdmin<'info> {
     )]
     pub admin_auth_badge: Account<'info, AuthBadgeState>,
 
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_with(&new_admin),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -34,14 +47,26 @@ pub fn add_admin_role(ctx: Context<AddAdmin>, new_admin: Pubkey) -> Result<()> {
     ctx.accounts.admin_auth_badge.set_inner(AuthBadgeState {
         address: new_admin,
         is_admin: true,
+        expires_at: 0,
+        payer: ctx.accounts.owner.key(),
+    });
+    ctx.accounts.role_registry.insert(new_admin, true);
+
+    emit_cpi!(AuthBadgeAdded {
+        address: new_admin,
+        is_admin: true,
+        signer: ctx.accounts.owner.key(),
+        slot: Clock::get()?.slot,
     });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RemoveAdmin<'info> {
     /// The signer can be the owner or an admin.
+    #[account(mut)]
     pub signer: Signer<'info>,
 
     /// Proof that the signer is authorized.
@@ -60,8 +85,32 @@ pub struct RemoveAdmin<'info> {
         ) @ PriceOracleError::OwnerDeletionForbidden,
     )]
     pub auth_badge_to_be_removed: Account<'info, AuthBadgeState>,
+
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_without(&auth_badge_to_be_removed.address),
+        realloc::payer = signer,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
+    pub system_program: Program<'info, System>,
 }
 
-pub fn remove_admin_role(_ctx: Context<RemoveAdmin>) -> Result<()> {
+pub fn remove_admin_role(ctx: Context<RemoveAdmin>) -> Result<()> {
+    ctx.accounts
+        .role_registry
+        .remove(&ctx.accounts.auth_badge_to_be_removed.address);
+
+    emit_cpi!(AuthBadgeRemoved {
+        address: ctx.accounts.auth_badge_to_be_removed.address,
+        is_admin: ctx.accounts.auth_badge_to_be_removed.is_admin,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
     Ok(())
 }
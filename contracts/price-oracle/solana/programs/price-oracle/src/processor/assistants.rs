@@ -1,6 +1,11 @@
-use crate::{error::PriceOracleError, state::AuthBadgeState};
+use crate::{
+    error::PriceOracleError,
+    events::{AuthBadgeAdded, AuthBadgeRemoved},
+    state::{AuthBadgeState, RoleRegistryState},
+};
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(new_assistant: Pubkey)]
 pub struct AddAssistant<'info> {
@@ -21,6 +26,17 @@ pub struct AddAssistant<'info> {
     )]
     pub assistant_auth_badge: Account<'info, AuthBadgeState>,
 
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_with(&new_assistant),
+        realloc::payer = signer,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -28,14 +44,26 @@ pub fn add_assistant_role(ctx: Context<AddAssistant>, new_assistant: Pubkey) ->
     ctx.accounts.assistant_auth_badge.set_inner(AuthBadgeState {
         address: new_assistant,
         is_admin: false,
+        expires_at: 0,
+        payer: ctx.accounts.signer.key(),
+    });
+    ctx.accounts.role_registry.insert(new_assistant, false);
+
+    emit_cpi!(AuthBadgeAdded {
+        address: new_assistant,
+        is_admin: false,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
     });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RemoveAssistant<'info> {
     /// The signer can be the owner or an admin.
+    #[account(mut)]
     pub signer: Signer<'info>,
 
     /// Proof that the signer is authorized.
@@ -45,11 +73,35 @@ pub struct RemoveAssistant<'info> {
     #[account(
         mut,
         close = signer,
-        constraint = auth_badge_to_be_removed.is_admin == false @ PriceOracleError::AssistantDeletionOnly,
+        constraint = !auth_badge_to_be_removed.is_admin @ PriceOracleError::AssistantDeletionOnly,
     )]
     pub auth_badge_to_be_removed: Account<'info, AuthBadgeState>,
+
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_without(&auth_badge_to_be_removed.address),
+        realloc::payer = signer,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
+    pub system_program: Program<'info, System>,
 }
 
-pub fn remove_assistant_role(_ctx: Context<RemoveAssistant>) -> Result<()> {
+pub fn remove_assistant_role(ctx: Context<RemoveAssistant>) -> Result<()> {
+    ctx.accounts
+        .role_registry
+        .remove(&ctx.accounts.auth_badge_to_be_removed.address);
+
+    emit_cpi!(AuthBadgeRemoved {
+        address: ctx.accounts.auth_badge_to_be_removed.address,
+        is_admin: false,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
     Ok(())
 }
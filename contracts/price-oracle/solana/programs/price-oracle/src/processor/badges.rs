@@ -0,0 +1,121 @@
+//! Expiration of the admin and assistant badges.
+
+use crate::{
+    error::PriceOracleError,
+    events::{AuthBadgeRemoved, AuthBadgeRenewed},
+    state::{AuthBadgeState, PriceOracleConfigState, RoleRegistryState},
+    utils::grow_account,
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RenewAuthBadge<'info> {
+    /// The signer must be the owner to renew an admin badge, or the owner or an admin to renew
+    /// an assistant badge. Pays for the badge to grow if it predates expiration.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// Program Config account. This program requires that the [`signer`] specified
+    /// in the context equals the owner role stored in the config to renew an admin badge.
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    #[account(
+        mut,
+        constraint = (
+            auth_badge_to_be_renewed.address != config.owner
+        ) @ PriceOracleError::OwnerBadgeCannotExpire,
+        constraint = (
+            !auth_badge_to_be_renewed.is_admin || signer.key() == config.owner
+        ) @ PriceOracleError::OwnerOnly,
+    )]
+    pub auth_badge_to_be_renewed: Account<'info, AuthBadgeState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn renew_auth_badge(ctx: Context<RenewAuthBadge>, expires_at: i64) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        expires_at == 0 || expires_at > clock.unix_timestamp,
+        PriceOracleError::InvalidExpiration
+    );
+
+    // Badges created before expiration was introduced need room for the new fields:
+    grow_account(
+        &ctx.accounts.auth_badge_to_be_renewed.to_account_info(),
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + AuthBadgeState::INIT_SPACE,
+    )?;
+
+    let signer = ctx.accounts.signer.key();
+    let badge = &mut ctx.accounts.auth_badge_to_be_renewed;
+    let old_expires_at = badge.expires_at;
+    badge.expires_at = expires_at;
+    if badge.payer == Pubkey::default() {
+        badge.payer = signer;
+    }
+
+    emit_cpi!(AuthBadgeRenewed {
+        address: badge.address,
+        is_admin: badge.is_admin,
+        old_expires_at,
+        new_expires_at: expires_at,
+        signer,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseExpiredAuthBadge<'info> {
+    /// Anyone can close an expired badge.
+    pub signer: Signer<'info>,
+
+    /// CHECK: the `has_one` constraint enforces that this is the account that paid for the badge.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        constraint = auth_badge_to_be_closed.is_expired() @ PriceOracleError::BadgeNotExpired,
+    )]
+    pub auth_badge_to_be_closed: Account<'info, AuthBadgeState>,
+
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_without(&auth_badge_to_be_closed.address),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn close_expired_auth_badge(ctx: Context<CloseExpiredAuthBadge>) -> Result<()> {
+    ctx.accounts
+        .role_registry
+        .remove(&ctx.accounts.auth_badge_to_be_closed.address);
+
+    emit_cpi!(AuthBadgeRemoved {
+        address: ctx.accounts.auth_badge_to_be_closed.address,
+        is_admin: ctx.accounts.auth_badge_to_be_closed.is_admin,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::{
     error::PriceOracleError,
-    state::{AuthBadgeState, PriceOracleConfigState},
+    state::{AuthBadgeState, PriceOracleConfigState, RoleRegistryState},
     utils::DrainAccount,
+    SEED_PREFIX_UPGRADE_LOCK,
 };
 use anchor_lang::{
     prelude::*,
@@ -10,6 +11,7 @@ use anchor_lang::{
 use std::ops::DerefMut;
 
 #[derive(Accounts)]
+#[instruction(admins: Vec<Pubkey>, assistants: Vec<Pubkey>)]
 pub struct Initialize<'info> {
     /// Since we are passing on the upgrade authority, the original deployer is the only one
     /// who can initialize the program.
@@ -41,6 +43,16 @@ pub struct Initialize<'info> {
     )]
     pub config: Account<'info, PriceOracleConfigState>,
 
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        init,
+        payer = deployer,
+        space = RoleRegistryState::space(1 + admins.len() + assistants.len()),
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
     #[account(
         mut,
         seeds = [crate::ID.as_ref()],
@@ -53,6 +65,11 @@ pub struct Initialize<'info> {
     #[account(address = bpf_loader_upgradeable::ID)]
     pub bpf_loader_upgradeable: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The seeds constraint enforces that this is the correct address.
+    /// Becomes the program authority, so that upgrades go through the timelock.
+    #[account(seeds = [SEED_PREFIX_UPGRADE_LOCK], bump)]
+    pub upgrade_lock: UncheckedAccount<'info>,
 }
 
 pub fn initialize<'a, 'b, 'c, 'info>(
@@ -60,22 +77,19 @@ pub fn initialize<'a, 'b, 'c, 'info>(
     admins: Vec<Pubkey>,
     assistants: Vec<Pubkey>,
 ) -> Result<()> {
-    //We only update the upgrade authority if the program wasn't deployed by the designated owner
-    if Some(ctx.accounts.owner.key()) != ctx.accounts.program_data.upgrade_authority_address {
-        //This call fails for anyone but the deployer who must be the current update authority.
-        invoke(
-            &bpf_loader_upgradeable::set_upgrade_authority(
-                &ctx.program_id,
-                &ctx.accounts.deployer.key(),
-                Some(&ctx.accounts.owner.key()),
-            ),
-            &[
-                ctx.accounts.program_data.to_account_info(),
-                ctx.accounts.deployer.to_account_info(),
-                ctx.accounts.owner.to_account_info(),
-            ],
-        )?;
-    }
+    //This call fails for anyone but the deployer who must be the current update authority.
+    invoke(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            ctx.program_id,
+            &ctx.accounts.deployer.key(),
+            Some(&ctx.accounts.upgrade_lock.key()),
+        ),
+        &[
+            ctx.accounts.program_data.to_account_info(),
+            ctx.accounts.deployer.to_account_info(),
+            ctx.accounts.upgrade_lock.to_account_info(),
+        ],
+    )?;
 
     ctx.accounts.config.set_inner(PriceOracleConfigState {
         owner: ctx.accounts.owner.key(),
@@ -86,7 +100,12 @@ pub fn initialize<'a, 'b, 'c, 'info>(
     ctx.accounts.owner_badge.set_inner(AuthBadgeState {
         address: ctx.accounts.owner.key(),
         is_admin: true,
+        expires_at: 0,
+        payer: ctx.accounts.deployer.key(),
     });
+    ctx.accounts
+        .role_registry
+        .insert(ctx.accounts.owner.key(), true);
 
     require_eq!(
         admins.len() + assistants.len(),
@@ -137,9 +156,15 @@ pub fn initialize<'a, 'b, 'c, 'info>(
         )?;
 
         AuthBadgeState::try_serialize(
-            &AuthBadgeState { address, is_admin },
+            &AuthBadgeState {
+                address,
+                is_admin,
+                expires_at: 0,
+                payer: ctx.accounts.deployer.key(),
+            },
             badge_acc_info.try_borrow_mut_data()?.deref_mut(),
         )?;
+        ctx.accounts.role_registry.insert(address, is_admin);
     }
 
     Ok(())
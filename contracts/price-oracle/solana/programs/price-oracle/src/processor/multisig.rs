@@ -0,0 +1,305 @@
+//! A native M-of-N multisig, whose authority can be set as the owner (see [`MultisigState`]).
+//!
+//! Switching between a single key and a multisig owner is a regular owner transfer: the pending
+//! owner is confirmed by the new key, or by a multisig proposal executing the confirmation.
+
+use crate::{
+    error::PriceOracleError,
+    events::{
+        MultisigProposalApproved, MultisigProposalCancelled, MultisigProposalCreated,
+        MultisigProposalExecuted, MultisigUpdated,
+    },
+    state::{MultisigProposalState, MultisigState, ProposedAccountMeta, StaleProposal},
+};
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MultisigState::INIT_SPACE,
+        seeds = [MultisigState::SEED_PREFIX, create_key.as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, MultisigState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_multisig(
+    ctx: Context<CreateMultisig>,
+    create_key: Pubkey,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        MultisigState::is_valid(&signers, threshold),
+        PriceOracleError::InvalidMultisig
+    );
+
+    ctx.accounts.multisig.set_inner(MultisigState {
+        create_key,
+        signers: signers.clone(),
+        threshold,
+        proposal_count: 0,
+        generation: 0,
+    });
+
+    emit_cpi!(MultisigUpdated {
+        multisig: ctx.accounts.multisig.key(),
+        authority: MultisigState::authority(&ctx.accounts.multisig.key()).0,
+        signers,
+        threshold,
+        signer: ctx.accounts.payer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateMultisig<'info> {
+    /// Only the multisig itself can change its signers, i.e. through one of its proposals.
+    #[account(
+        seeds = [MultisigState::AUTHORITY_SEED_PREFIX, multisig.key().as_ref()],
+        bump
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Account<'info, MultisigState>,
+}
+
+pub fn update_multisig(
+    ctx: Context<UpdateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        MultisigState::is_valid(&signers, threshold),
+        PriceOracleError::InvalidMultisig
+    );
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.signers = signers.clone();
+    multisig.threshold = threshold;
+    // Pending proposals were approved by, and counted against, the previous signers:
+    multisig.generation += 1;
+
+    emit_cpi!(MultisigUpdated {
+        multisig: multisig.key(),
+        authority: ctx.accounts.authority.key(),
+        signers,
+        threshold,
+        signer: ctx.accounts.authority.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(accounts: Vec<ProposedAccountMeta>, data: Vec<u8>)]
+pub struct ProposeMultisigInstruction<'info> {
+    /// The signer must be one of the multisig signers, and approves the proposal right away.
+    #[account(
+        mut,
+        constraint = multisig.is_signer(&proposer) @ PriceOracleError::MultisigSignerOnly,
+    )]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Account<'info, MultisigState>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigProposalState::space(accounts.len(), data.len()),
+        seeds = [
+            MultisigProposalState::SEED_PREFIX,
+            multisig.key().as_ref(),
+            multisig.proposal_count.to_be_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, MultisigProposalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_multisig_instruction(
+    ctx: Context<ProposeMultisigInstruction>,
+    accounts: Vec<ProposedAccountMeta>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let index = multisig.proposal_count;
+    multisig.proposal_count += 1;
+
+    let proposer = ctx.accounts.proposer.key();
+    let clock = Clock::get()?;
+    let expires_at = clock.unix_timestamp + StaleProposal::LIFETIME;
+    ctx.accounts.proposal.set_inner(MultisigProposalState {
+        multisig: multisig.key(),
+        index,
+        proposer,
+        generation: multisig.generation,
+        expires_at,
+        accounts,
+        data,
+        approvals: vec![proposer],
+    });
+
+    emit_cpi!(MultisigProposalCreated {
+        multisig: multisig.key(),
+        proposal: ctx.accounts.proposal.key(),
+        index,
+        expires_at,
+        signer: proposer,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApproveMultisigProposal<'info> {
+    #[account(constraint = multisig.is_signer(&signer) @ PriceOracleError::MultisigSignerOnly)]
+    pub signer: Signer<'info>,
+
+    pub multisig: Account<'info, MultisigState>,
+
+    #[account(mut, has_one = multisig)]
+    pub proposal: Account<'info, MultisigProposalState>,
+}
+
+pub fn approve_multisig_proposal(ctx: Context<ApproveMultisigProposal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let multisig = &ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.check_pending(multisig, Clock::get()?.unix_timestamp)?;
+    require!(
+        !proposal.approvals.contains(&signer),
+        PriceOracleError::AlreadyApproved
+    );
+    // Make room for the approval by dropping the ones of former signers:
+    proposal
+        .approvals
+        .retain(|approval| multisig.is_signer(approval));
+    proposal.approvals.push(signer);
+
+    emit_cpi!(MultisigProposalApproved {
+        proposal: proposal.key(),
+        approval_count: proposal.approval_count(multisig) as u8,
+        signer,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteMultisigProposal<'info> {
+    /// Anyone can execute a proposal once it has been approved by enough signers.
+    pub signer: Signer<'info>,
+
+    pub multisig: Account<'info, MultisigState>,
+
+    /// CHECK: the seeds constraint enforces that this is the authority of the multisig.
+    #[account(
+        mut,
+        seeds = [MultisigState::AUTHORITY_SEED_PREFIX, multisig.key().as_ref()],
+        bump
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = multisig,
+        has_one = proposer,
+        constraint = (
+            proposal.approval_count(&multisig) >= usize::from(multisig.threshold)
+        ) @ PriceOracleError::NotEnoughApprovals,
+    )]
+    pub proposal: Account<'info, MultisigProposalState>,
+
+    /// CHECK: the `has_one` constraint enforces that this is the proposer.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+/// The accounts of the proposed instruction are passed in `remaining_accounts`.
+pub fn execute_multisig_proposal<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteMultisigProposal<'info>>,
+) -> Result<()> {
+    ctx.accounts
+        .proposal
+        .check_pending(&ctx.accounts.multisig, Clock::get()?.unix_timestamp)?;
+
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.authority.to_account_info());
+    account_infos.push(ctx.accounts.program.to_account_info());
+
+    invoke_signed(
+        &ctx.accounts.proposal.instruction(),
+        &account_infos,
+        &[&[
+            MultisigState::AUTHORITY_SEED_PREFIX,
+            ctx.accounts.multisig.key().as_ref(),
+            &[ctx.bumps.authority],
+        ]],
+    )?;
+
+    emit_cpi!(MultisigProposalExecuted {
+        proposal: ctx.accounts.proposal.key(),
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelMultisigProposal<'info> {
+    /// The signer must be one of the multisig signers or its authority, i.e. through another
+    /// proposal. Anyone can cancel a proposal that can't be executed anymore.
+    #[account(
+        constraint = (
+            multisig.is_signer(&signer)
+                || signer.key() == MultisigState::authority(&multisig.key()).0
+                || proposal.check_pending(&multisig, Clock::get()?.unix_timestamp).is_err()
+        ) @ PriceOracleError::MultisigSignerOnly,
+    )]
+    pub signer: Signer<'info>,
+
+    pub multisig: Account<'info, MultisigState>,
+
+    #[account(mut, close = proposer, has_one = multisig, has_one = proposer)]
+    pub proposal: Account<'info, MultisigProposalState>,
+
+    /// CHECK: the `has_one` constraint enforces that this is the proposer.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+pub fn cancel_multisig_proposal(ctx: Context<CancelMultisigProposal>) -> Result<()> {
+    emit_cpi!(MultisigProposalCancelled {
+        proposal: ctx.accounts.proposal.key(),
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
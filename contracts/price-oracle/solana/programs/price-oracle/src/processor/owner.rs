@@ -2,7 +2,8 @@
 
 use crate::{
     error::PriceOracleError,
-    state::{AuthBadgeState, PriceOracleConfigState},
+    state::{AuthBadgeState, PriceOracleConfigState, RoleRegistryState},
+    utils::grow_account,
     SEED_PREFIX_UPGRADE_LOCK,
 };
 use anchor_lang::{
@@ -17,7 +18,7 @@ pub struct OwnerContext<'info> {
         seeds = [SEED_PREFIX_UPGRADE_LOCK],
         bump,
     )]
-    upgrade_lock: UncheckedAccount<'info>,
+    pub upgrade_lock: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -25,13 +26,43 @@ pub struct OwnerContext<'info> {
         bump,
         seeds::program = bpf_loader_upgradeable::ID,
     )]
-    program_data: Account<'info, ProgramData>,
+    pub program_data: Account<'info, ProgramData>,
 
     /// CHECK: The BPF loader program.
     #[account(address = bpf_loader_upgradeable::ID)]
     pub bpf_loader_upgradeable: UncheckedAccount<'info>,
 }
 
+impl<'info> OwnerContext<'info> {
+    /// Hands the program authority over to the upgrade lock for good, so that the program can
+    /// only be upgraded through a timelocked proposal. Programs initialized before that still have
+    /// the owner as their authority until the first ownership transfer or upgrade proposal.
+    pub fn lock_upgrade_authority(
+        &self,
+        owner: &Signer<'info>,
+        upgrade_lock_bump: u8,
+    ) -> Result<()> {
+        if self.program_data.upgrade_authority_address == Some(self.upgrade_lock.key()) {
+            return Ok(());
+        }
+
+        invoke_signed(
+            &bpf_loader_upgradeable::set_upgrade_authority_checked(
+                &crate::ID,
+                &owner.key(),
+                &self.upgrade_lock.key(),
+            ),
+            &[
+                self.program_data.to_account_info(),
+                owner.to_account_info(),
+                self.upgrade_lock.to_account_info(),
+            ],
+            &[&[SEED_PREFIX_UPGRADE_LOCK, &[upgrade_lock_bump]]],
+        )?;
+
+        Ok(())
+    }
+}
 
 #[derive(Accounts)]
 pub struct SubmitOwnerTransfer<'info> {
@@ -62,23 +93,10 @@ pub fn submit_owner_role_transfer_request(
 
     ctx.accounts.config.pending_owner = Some(new_owner);
 
-    // Change the program authority to the upgrade lock PDA, so that the owner does not need
-    // to sign the transaction when confirming the ownership transfer:
-    invoke_signed(
-        &bpf_loader_upgradeable::set_upgrade_authority_checked(
-            &ctx.program_id,
-            &ctx.accounts.owner.key(),
-            &ctx.accounts.owner_ctx.upgrade_lock.key(),
-        ),
-        &[
-            ctx.accounts.owner_ctx.program_data.to_account_info(),
-            ctx.accounts.owner.to_account_info(),
-            ctx.accounts.owner_ctx.upgrade_lock.to_account_info(),
-        ],
-        &[&[SEED_PREFIX_UPGRADE_LOCK, &[ctx.bumps.owner_ctx.upgrade_lock]]],
-    )?;
-
-    Ok(())
+    // The new owner doesn't take over the program authority, it stays with the upgrade lock:
+    ctx.accounts
+        .owner_ctx
+        .lock_upgrade_authority(&ctx.accounts.owner, ctx.bumps.owner_ctx.upgrade_lock)
 }
 
 #[derive(Accounts)]
@@ -112,6 +130,19 @@ pub struct ConfirmOwnerTransfer<'info> {
     )]
     pub config: Account<'info, PriceOracleConfigState>,
 
+    /// The list of badge holders, kept in sync with the badges.
+    #[account(
+        mut,
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump,
+        realloc = role_registry.space_with_owner(&config.owner, &new_owner.key()),
+        realloc::payer = new_owner,
+        realloc::zero = false,
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
+    /// Unused since the program authority stays with the upgrade lock, only kept so that the
+    /// accounts of existing clients still match.
     pub owner_ctx: OwnerContext<'info>,
 
     pub system_program: Program<'info, System>,
@@ -120,27 +151,27 @@ pub struct ConfirmOwnerTransfer<'info> {
 pub fn confirm_owner_role_transfer_request(ctx: Context<ConfirmOwnerTransfer>) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
-    // Change the program authority to the new owner:
-    invoke_signed(
-        &bpf_loader_upgradeable::set_upgrade_authority(
-            &ctx.program_id,
-            &ctx.accounts.owner_ctx.upgrade_lock.key(),
-            Some(&ctx.accounts.new_owner.key()),
-        ),
-        &[
-            ctx.accounts.owner_ctx.program_data.to_account_info(),
-            ctx.accounts.owner_ctx.upgrade_lock.to_account_info(),
-            ctx.accounts.new_owner.to_account_info(),
-        ],
-        &[&[SEED_PREFIX_UPGRADE_LOCK, &[ctx.bumps.owner_ctx.upgrade_lock]]],
-    )?;
+    let role_registry = &mut ctx.accounts.role_registry;
+    role_registry.remove(&config.owner);
+    role_registry.insert(ctx.accounts.new_owner.key(), true);
 
     config.owner = ctx.accounts.new_owner.key();
     config.pending_owner = None;
 
+    // The new owner may already hold a badge created before expiration was introduced:
+    grow_account(
+        &ctx.accounts.auth_badge_new_owner.to_account_info(),
+        &ctx.accounts.new_owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + AuthBadgeState::INIT_SPACE,
+    )?;
+
+    // The owner badge never expires, even if the new owner previously held an expiring badge:
     ctx.accounts.auth_badge_new_owner.set_inner(AuthBadgeState {
         address: ctx.accounts.new_owner.key(),
         is_admin: true,
+        expires_at: 0,
+        payer: ctx.accounts.new_owner.key(),
     });
 
     Ok(())
@@ -159,26 +190,13 @@ pub struct CancelOwnerTransfer<'info> {
     )]
     pub config: Account<'info, PriceOracleConfigState>,
 
+    /// Unused since the program authority stays with the upgrade lock, only kept so that the
+    /// accounts of existing clients still match.
     pub owner_ctx: OwnerContext<'info>,
 }
 
 pub fn cancel_owner_role_transfer_request(ctx: Context<CancelOwnerTransfer>) -> Result<()> {
     ctx.accounts.config.pending_owner = None;
 
-    // Transfer the program authority back to the owner:
-    invoke_signed(
-        &bpf_loader_upgradeable::set_upgrade_authority(
-            &ctx.program_id,
-            &ctx.accounts.owner_ctx.upgrade_lock.key(),
-            Some(&ctx.accounts.owner.key()),
-        ),
-        &[
-            ctx.accounts.owner_ctx.program_data.to_account_info(),
-            ctx.accounts.owner_ctx.upgrade_lock.to_account_info(),
-            ctx.accounts.owner.to_account_info(),
-        ],
-        &[&[SEED_PREFIX_UPGRADE_LOCK, &[ctx.bumps.owner_ctx.upgrade_lock]]],
-    )?;
-
     Ok(())
 }
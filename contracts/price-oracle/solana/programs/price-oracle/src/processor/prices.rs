@@ -1,9 +1,16 @@
 use crate::{
     error::PriceOracleError,
-    state::{AuthBadgeState, PricesState, PricesStatePlatform},
+    events::{EvmPricesUpdated, PricesDeregistered, PricesPrecisionUpdated, SuiPricesUpdated},
+    state::{
+        AssistantScopeState, AuthBadgeState, PriceHistoryState, PriceObservation, PriceTarget,
+        PricesState, PricesStatePlatform, ZeroCopyPricesState,
+    },
+    utils::{close_account, Platform},
 };
 use anchor_lang::prelude::*;
+use bytemuck::Zeroable;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(chain_id: u16)]
 pub struct RegisterPrices<'info> {
@@ -14,6 +21,18 @@ pub struct RegisterPrices<'info> {
     #[account(constraint = &auth_badge.is_assistant(&signer) @ PriceOracleError::AuthorizedOnly)]
     pub auth_badge: Account<'info, AuthBadgeState>,
 
+    /// CHECK: the signer's scope, which may not exist. See [`AssistantScopeState`].
+    #[account(
+        seeds = [AssistantScopeState::SEED_PREFIX, signer.key().as_ref()],
+        bump,
+        constraint = AssistantScopeState::permits(
+            &auth_badge,
+            &scope,
+            PriceTarget::Chain(chain_id),
+        ) @ PriceOracleError::OutOfScope,
+    )]
+    pub scope: UncheckedAccount<'info>,
+
     /// The prices for the given chain ID.
     #[account(
         init,
@@ -35,10 +54,11 @@ pub fn register_evm_prices(
     price_per_tx_byte: u32,
 ) -> Result<()> {
     require!(
-        crate::utils::Platform::from_chain_id(chain_id) == Some(crate::utils::Platform::Evm),
+        Platform::from_chain_id(chain_id) == Some(Platform::Evm),
         PriceOracleError::InvalidChainId
     );
 
+    let old_prices = *ctx.accounts.prices;
     ctx.accounts.prices.set_inner(PricesState {
         chain_id,
         gas_token_price,
@@ -46,8 +66,15 @@ pub fn register_evm_prices(
             gas_price,
             price_per_tx_byte,
         },
+        precision: 0,
     });
 
+    emit_cpi!(EvmPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
     Ok(())
 }
 
@@ -60,10 +87,11 @@ pub fn register_sui_prices(
     rebate_ratio: u8,
 ) -> Result<()> {
     require!(
-        crate::utils::Platform::from_chain_id(chain_id) == Some(crate::utils::Platform::Sui),
+        Platform::from_chain_id(chain_id) == Some(Platform::Sui),
         PriceOracleError::InvalidChainId
     );
 
+    let old_prices = *ctx.accounts.prices;
     ctx.accounts.prices.set_inner(PricesState {
         chain_id,
         gas_token_price,
@@ -72,11 +100,19 @@ pub fn register_sui_prices(
             byte_price,
             rebate_ratio,
         },
+        precision: 0,
     });
 
+    emit_cpi!(SuiPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdatePrices<'info> {
     pub signer: Signer<'info>,
@@ -85,9 +121,111 @@ pub struct UpdatePrices<'info> {
     #[account(constraint = &auth_badge.is_assistant(&signer) @ PriceOracleError::AuthorizedOnly)]
     pub auth_badge: Account<'info, AuthBadgeState>,
 
+    /// CHECK: the signer's scope, which may not exist. See [`AssistantScopeState`].
+    #[account(
+        seeds = [AssistantScopeState::SEED_PREFIX, signer.key().as_ref()],
+        bump,
+        constraint = AssistantScopeState::permits(
+            &auth_badge,
+            &scope,
+            PriceTarget::Chain(prices.chain_id),
+        ) @ PriceOracleError::OutOfScope,
+    )]
+    pub scope: UncheckedAccount<'info>,
+
     /// The prices for the given chain ID.
     #[account(mut)]
     pub prices: Account<'info, PricesState>,
+
+    /// CHECK: the price history of the chain, which may not exist. Updates are recorded in it if
+    /// it does, so that they can't skip it. See [`PriceHistoryState`].
+    #[account(
+        mut,
+        seeds = [PriceHistoryState::SEED_PREFIX, prices.chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub price_history: UncheckedAccount<'info>,
+}
+
+/// Applies `update` to the price history if the chain keeps one, i.e. if the account exists.
+///
+/// Its derivation has to be checked by the caller, which also ensures that it's owned by us.
+fn update_price_history(
+    price_history: &AccountInfo,
+    update: impl FnOnce(&mut PriceHistoryState),
+) -> Result<()> {
+    if price_history.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut data = price_history.try_borrow_mut_data()?;
+    let mut history = Box::new(PriceHistoryState::try_deserialize(&mut &data[..])?);
+    update(&mut history);
+    history.try_serialize(&mut &mut data[..])
+}
+
+fn record_price_history(accounts: &UpdatePrices) -> Result<()> {
+    let observation = PriceObservation::new(&accounts.prices, Clock::get()?.unix_timestamp);
+    update_price_history(&accounts.price_history, |history| {
+        history.record(observation)
+    })
+}
+
+/// Discards all observations of the price history (if any) in favor of the given prices.
+fn restart_price_history(price_history: &AccountInfo, prices: &PricesState) -> Result<()> {
+    let observation = PriceObservation::new(prices, Clock::get()?.unix_timestamp);
+    update_price_history(price_history, |history| {
+        history.next_index = 0;
+        history.count = 0;
+        history.record(observation);
+    })
+}
+
+#[derive(Accounts)]
+pub struct InitPriceHistory<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_assistant(&signer) @ PriceOracleError::AuthorizedOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// CHECK: the signer's scope, which may not exist. See [`AssistantScopeState`].
+    #[account(
+        seeds = [AssistantScopeState::SEED_PREFIX, signer.key().as_ref()],
+        bump,
+        constraint = AssistantScopeState::permits(
+            &auth_badge,
+            &scope,
+            PriceTarget::Chain(prices.chain_id),
+        ) @ PriceOracleError::OutOfScope,
+    )]
+    pub scope: UncheckedAccount<'info>,
+
+    /// The registered prices of the chain, recorded as the first observation.
+    pub prices: Account<'info, PricesState>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + PriceHistoryState::INIT_SPACE,
+        seeds = [PriceHistoryState::SEED_PREFIX, prices.chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistoryState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_price_history(ctx: Context<InitPriceHistory>) -> Result<()> {
+    let price_history = &mut ctx.accounts.price_history;
+    price_history.chain_id = ctx.accounts.prices.chain_id;
+    price_history.record(PriceObservation::new(
+        &ctx.accounts.prices,
+        Clock::get()?.unix_timestamp,
+    ));
+
+    Ok(())
 }
 
 pub fn update_evm_prices(
@@ -96,6 +234,8 @@ pub fn update_evm_prices(
     new_gas_price: u32,
     new_price_per_tx_byte: u32,
 ) -> Result<()> {
+    let old_prices = *ctx.accounts.prices;
+
     ctx.accounts.prices.gas_token_price = new_gas_token_price;
 
     let PricesStatePlatform::Evm {
@@ -109,6 +249,14 @@ pub fn update_evm_prices(
     *gas_price = new_gas_price;
     *price_per_tx_byte = new_price_per_tx_byte;
 
+    record_price_history(ctx.accounts)?;
+
+    emit_cpi!(EvmPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
     Ok(())
 }
 
@@ -117,6 +265,8 @@ pub fn update_sui_prices(
     new_computation_unit_price: u32,
     new_gas_token_price: u64,
 ) -> Result<()> {
+    let old_prices = *ctx.accounts.prices;
+
     ctx.accounts.prices.gas_token_price = new_gas_token_price;
 
     let PricesStatePlatform::Sui {
@@ -129,25 +279,265 @@ pub fn update_sui_prices(
 
     *computation_unit_price = new_computation_unit_price;
 
+    record_price_history(ctx.accounts)?;
+
+    emit_cpi!(SuiPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
     Ok(())
 }
 
 pub fn update_sui_byte_price(ctx: Context<UpdatePrices>, new_byte_price: u32) -> Result<()> {
+    let old_prices = *ctx.accounts.prices;
+
     let PricesStatePlatform::Sui { byte_price, .. } = &mut ctx.accounts.prices.prices else {
         return Err(PriceOracleError::InvalidChainId.into());
     };
 
     *byte_price = new_byte_price;
 
+    record_price_history(ctx.accounts)?;
+
+    emit_cpi!(SuiPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
     Ok(())
 }
 
 pub fn update_sui_rebate_ratio(ctx: Context<UpdatePrices>, new_rebate_ratio: u8) -> Result<()> {
+    let old_prices = *ctx.accounts.prices;
+
     let PricesStatePlatform::Sui { rebate_ratio, .. } = &mut ctx.accounts.prices.prices else {
         return Err(PriceOracleError::InvalidChainId.into());
     };
 
     *rebate_ratio = new_rebate_ratio;
 
+    record_price_history(ctx.accounts)?;
+
+    emit_cpi!(SuiPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &ctx.accounts.prices,
+    )?);
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePricesPrecision<'info> {
+    /// The signer can be the owner or an admin.
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// The prices to rescale.
+    #[account(mut)]
+    pub prices: Account<'info, PricesState>,
+
+    /// CHECK: the price history of the chain, which may not exist. Its observations are
+    /// discarded since they have the old precision.
+    #[account(
+        mut,
+        seeds = [PriceHistoryState::SEED_PREFIX, prices.chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub price_history: UncheckedAccount<'info>,
+}
+
+pub fn update_prices_precision(
+    ctx: Context<UpdatePricesPrecision>,
+    new_precision: u8,
+) -> Result<()> {
+    let old_precision = ctx.accounts.prices.precision;
+    let new_prices = ctx.accounts.prices.with_precision(new_precision)?;
+    ctx.accounts.prices.set_inner(new_prices);
+
+    restart_price_history(&ctx.accounts.price_history, &new_prices)?;
+
+    emit_cpi!(PricesPrecisionUpdated {
+        chain_id: new_prices.chain_id,
+        old_precision,
+        new_precision,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeregisterPrices<'info> {
+    /// The signer can be the owner or an admin.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// The prices to close, the rent goes to the signer.
+    #[account(mut, close = signer)]
+    pub prices: Account<'info, PricesState>,
+
+    /// CHECK: the price history of the chain, which may not exist. It has to be closed along
+    /// with the prices.
+    #[account(
+        mut,
+        seeds = [PriceHistoryState::SEED_PREFIX, prices.chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub price_history: UncheckedAccount<'info>,
+}
+
+pub fn deregister_prices(ctx: Context<DeregisterPrices>) -> Result<()> {
+    if !ctx.accounts.price_history.data_is_empty() {
+        close_account(&ctx.accounts.price_history, &ctx.accounts.signer)?;
+    }
+
+    emit_cpi!(PricesDeregistered {
+        chain_id: ctx.accounts.prices.chain_id,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+/// Overwrites registered prices regardless of their current platform, e.g. if the platform of a
+/// chain id changed (see [`Platform::from_chain_id`]) and the account can't be read anymore.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(chain_id: u16)]
+pub struct ResetPrices<'info> {
+    /// The signer can be the owner or an admin.
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// CHECK: registered prices for the given chain ID, not deserialized since their layout might
+    /// not match the platform of the chain anymore.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [PricesState::SEED_PREFIX, chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub prices: UncheckedAccount<'info>,
+
+    /// CHECK: the price history of the chain, which may not exist. Its observations are
+    /// discarded since they might belong to the other platform.
+    #[account(
+        mut,
+        seeds = [PriceHistoryState::SEED_PREFIX, chain_id.to_be_bytes().as_ref()],
+        bump
+    )]
+    pub price_history: UncheckedAccount<'info>,
+}
+
+/// Returns the prices that were overwritten, see [`prices_before_reset`].
+fn reset_prices(accounts: &mut ResetPrices, new_prices: &PricesState) -> Result<PricesState> {
+    let mut data = accounts.prices.try_borrow_mut_data()?;
+    require!(
+        data.starts_with(PricesState::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let old_prices = prices_before_reset(&data[PricesState::DISCRIMINATOR.len()..]);
+    // The platforms have different sizes, so we must not leave any stale bytes behind:
+    data.fill(0);
+    new_prices.try_serialize(&mut &mut data[..])?;
+
+    restart_price_history(&accounts.price_history, new_prices)?;
+
+    Ok(old_prices)
+}
+
+/// The prices that are about to be reset, read with the layout of the chain's current platform
+/// since the one they were written with is unknown. Unreadable prices count as uninitialized.
+fn prices_before_reset(data: &[u8]) -> PricesState {
+    let mut raw = ZeroCopyPricesState::zeroed();
+    let raw_bytes = bytemuck::bytes_of_mut(&mut raw);
+    let len = raw_bytes.len().min(data.len());
+    raw_bytes[..len].copy_from_slice(&data[..len]);
+
+    raw.prices_state()
+        .unwrap_or_else(|_| PricesState::uninitialized())
+}
+
+pub fn reset_evm_prices(
+    ctx: Context<ResetPrices>,
+    chain_id: u16,
+    gas_token_price: u64,
+    gas_price: u32,
+    price_per_tx_byte: u32,
+) -> Result<()> {
+    require!(
+        Platform::from_chain_id(chain_id) == Some(Platform::Evm),
+        PriceOracleError::InvalidChainId
+    );
+
+    let new_prices = PricesState {
+        chain_id,
+        gas_token_price,
+        prices: PricesStatePlatform::Evm {
+            gas_price,
+            price_per_tx_byte,
+        },
+        precision: 0,
+    };
+    let old_prices = reset_prices(ctx.accounts, &new_prices)?;
+
+    emit_cpi!(EvmPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &new_prices,
+    )?);
+
+    Ok(())
+}
+
+pub fn reset_sui_prices(
+    ctx: Context<ResetPrices>,
+    chain_id: u16,
+    gas_token_price: u64,
+    computation_unit_price: u32,
+    byte_price: u32,
+    rebate_ratio: u8,
+) -> Result<()> {
+    require!(
+        Platform::from_chain_id(chain_id) == Some(Platform::Sui),
+        PriceOracleError::InvalidChainId
+    );
+
+    let new_prices = PricesState {
+        chain_id,
+        gas_token_price,
+        prices: PricesStatePlatform::Sui {
+            computation_unit_price,
+            byte_price,
+            rebate_ratio,
+        },
+        precision: 0,
+    };
+    let old_prices = reset_prices(ctx.accounts, &new_prices)?;
+
+    emit_cpi!(SuiPricesUpdated::new(
+        ctx.accounts.signer.key(),
+        &old_prices,
+        &new_prices,
+    )?);
+
     Ok(())
 }
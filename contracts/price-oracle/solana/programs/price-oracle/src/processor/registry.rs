@@ -0,0 +1,75 @@
+//! Creation of the role registry for programs initialized before it existed.
+
+use crate::{
+    error::PriceOracleError,
+    state::{AuthBadgeState, PriceOracleConfigState, RoleRegistryState},
+    utils::grow_account,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitRoleRegistry<'info> {
+    /// The signer must be the owner.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Program Config account. This program requires that the [`owner`] specified
+    /// in the context equals the owner role stored in the config.
+    #[account(has_one = owner @ PriceOracleError::OwnerOnly)]
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = RoleRegistryState::space(0),
+        seeds = [RoleRegistryState::SEED_PREFIX],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistryState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Lists the badges passed in `remaining_accounts`, which should be all the existing ones.
+pub fn init_role_registry<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitRoleRegistry<'info>>,
+) -> Result<()> {
+    grow_account(
+        &ctx.accounts.role_registry.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        RoleRegistryState::space(ctx.remaining_accounts.len()),
+    )?;
+
+    for badge_acc_info in ctx.remaining_accounts {
+        require_keys_eq!(
+            *badge_acc_info.owner,
+            crate::ID,
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        let badge = AuthBadgeState::try_deserialize(&mut &badge_acc_info.try_borrow_data()?[..])?;
+
+        let (expected_address, _bump) = Pubkey::find_program_address(
+            &[
+                AuthBadgeState::SEED_PREFIX,
+                badge.address.to_bytes().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            badge_acc_info.key(),
+            expected_address,
+            ErrorCode::ConstraintSeeds
+        );
+        require!(
+            !ctx.accounts.role_registry.contains(&badge.address),
+            PriceOracleError::DuplicateBadge
+        );
+
+        ctx.accounts
+            .role_registry
+            .insert(badge.address, badge.is_admin);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::{
+    error::PriceOracleError,
+    events::AssistantScopeUpdated,
+    state::{AssistantScopeState, AuthBadgeState, MAX_SCOPED_CHAIN_IDS},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(assistant: Pubkey)]
+pub struct SetAssistantScope<'info> {
+    /// The signer can be the owner or an admin.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + AssistantScopeState::INIT_SPACE,
+        seeds = [AssistantScopeState::SEED_PREFIX, assistant.as_ref()],
+        bump
+    )]
+    pub scope: Account<'info, AssistantScopeState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_assistant_scope(
+    ctx: Context<SetAssistantScope>,
+    assistant: Pubkey,
+    sol_price: bool,
+    platforms: u8,
+    chain_ids: Vec<u16>,
+) -> Result<()> {
+    require!(
+        chain_ids.len() <= MAX_SCOPED_CHAIN_IDS,
+        PriceOracleError::TooManyScopedChains
+    );
+
+    ctx.accounts.scope.set_inner(AssistantScopeState {
+        assistant,
+        sol_price,
+        platforms,
+        chain_ids: chain_ids.clone(),
+    });
+
+    emit_cpi!(AssistantScopeUpdated {
+        assistant,
+        is_scoped: true,
+        sol_price,
+        platforms,
+        chain_ids,
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveAssistantScope<'info> {
+    /// The signer can be the owner or an admin.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Proof that the signer is authorized.
+    #[account(constraint = &auth_badge.is_admin(&signer) @ PriceOracleError::OwnerOrAdminOnly)]
+    pub auth_badge: Account<'info, AuthBadgeState>,
+
+    /// The assistant is unrestricted again once its scope is closed.
+    #[account(mut, close = signer)]
+    pub scope: Account<'info, AssistantScopeState>,
+}
+
+pub fn remove_assistant_scope(ctx: Context<RemoveAssistantScope>) -> Result<()> {
+    emit_cpi!(AssistantScopeUpdated {
+        assistant: ctx.accounts.scope.assistant,
+        is_scoped: false,
+        sol_price: true,
+        platforms: u8::MAX,
+        chain_ids: vec![],
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use crate::{
     error::PriceOracleError,
-    state::{AuthBadgeState, PriceOracleConfigState},
+    events::SolPriceUpdated,
+    state::{AssistantScopeState, AuthBadgeState, PriceOracleConfigState, PriceTarget},
 };
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateSolPrice<'info> {
     /// Any authorized account: owner, admin or assistant.
@@ -13,6 +15,18 @@ pub struct UpdateSolPrice<'info> {
     #[account(constraint = &auth_badge.is_assistant(&signer) @ PriceOracleError::AuthorizedOnly)]
     pub auth_badge: Account<'info, AuthBadgeState>,
 
+    /// CHECK: the signer's scope, which may not exist. See [`AssistantScopeState`].
+    #[account(
+        seeds = [AssistantScopeState::SEED_PREFIX, signer.key().as_ref()],
+        bump,
+        constraint = AssistantScopeState::permits(
+            &auth_badge,
+            &scope,
+            PriceTarget::SolPrice,
+        ) @ PriceOracleError::OutOfScope,
+    )]
+    pub scope: UncheckedAccount<'info>,
+
     /// This program Config account. This program requires that the [`signer`]
     /// specified in the context equals a pubkey specified in this account.
     /// Mutable, because we will update the `sol_price` field.
@@ -21,7 +35,14 @@ pub struct UpdateSolPrice<'info> {
 }
 
 pub fn update_sol_price(ctx: Context<UpdateSolPrice>, new_sol_price: u64) -> Result<()> {
-    ctx.accounts.config.sol_price = new_sol_price;
+    let old_sol_price = std::mem::replace(&mut ctx.accounts.config.sol_price, new_sol_price);
+
+    emit_cpi!(SolPriceUpdated {
+        signer: ctx.accounts.signer.key(),
+        slot: Clock::get()?.slot,
+        old_sol_price,
+        new_sol_price,
+    });
 
     Ok(())
 }
@@ -0,0 +1,292 @@
+//! Timelocked upgrades, executed by the program through the upgrade lock.
+//!
+//! The upgrade lock is the authority of the program for good, so that it can't be upgraded
+//! out-of-band, and of the buffer while its upgrade is pending, so that it can't be changed. Once
+//! the upgrade is cancelled, the buffer authority goes back to the owner.
+
+use super::owner::*;
+use crate::{
+    error::PriceOracleError,
+    events::{UpgradeCancelled, UpgradeDelayUpdated, UpgradeExecuted, UpgradeProposed},
+    state::{PriceOracleConfigState, UpgradeProposalState, UpgradeTimelockState},
+    SEED_PREFIX_UPGRADE_LOCK,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        program::invoke_signed,
+    },
+};
+use solana_sha256_hasher::hash;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetUpgradeDelay<'info> {
+    /// The signer must be the owner.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Program Config account. This program requires that the [`owner`] specified
+    /// in the context equals the owner role stored in the config.
+    #[account(has_one = owner @ PriceOracleError::OwnerOnly)]
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UpgradeTimelockState::INIT_SPACE,
+        seeds = [UpgradeTimelockState::SEED_PREFIX],
+        bump
+    )]
+    pub timelock: Account<'info, UpgradeTimelockState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Only applies to the upgrades proposed afterwards.
+pub fn set_upgrade_delay(ctx: Context<SetUpgradeDelay>, delay: u32) -> Result<()> {
+    let old_delay = ctx.accounts.timelock.delay;
+    ctx.accounts.timelock.delay = delay;
+
+    emit_cpi!(UpgradeDelayUpdated {
+        old_delay,
+        new_delay: delay,
+        signer: ctx.accounts.owner.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeUpgrade<'info> {
+    /// The signer must be the owner, and the current authority of the buffer.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Program Config account. This program requires that the [`owner`] specified
+    /// in the context equals the owner role stored in the config.
+    #[account(has_one = owner @ PriceOracleError::OwnerOnly)]
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    #[account(seeds = [UpgradeTimelockState::SEED_PREFIX], bump)]
+    pub timelock: Account<'info, UpgradeTimelockState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UpgradeProposalState::INIT_SPACE,
+        seeds = [UpgradeProposalState::SEED_PREFIX],
+        bump
+    )]
+    pub proposal: Account<'info, UpgradeProposalState>,
+
+    /// CHECK: the loader checks that this is a buffer of which the owner is the authority.
+    #[account(mut, owner = bpf_loader_upgradeable::ID)]
+    pub buffer: UncheckedAccount<'info>,
+
+    pub owner_ctx: OwnerContext<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_upgrade(ctx: Context<ProposeUpgrade>) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let upgrade_lock = &ctx.accounts.owner_ctx.upgrade_lock;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_PREFIX_UPGRADE_LOCK,
+        &[ctx.bumps.owner_ctx.upgrade_lock],
+    ]];
+
+    // Freeze the buffer:
+    invoke_signed(
+        &bpf_loader_upgradeable::set_buffer_authority_checked(
+            &ctx.accounts.buffer.key(),
+            &owner.key(),
+            &upgrade_lock.key(),
+        ),
+        &[
+            ctx.accounts.buffer.to_account_info(),
+            owner.to_account_info(),
+            upgrade_lock.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    ctx.accounts
+        .owner_ctx
+        .lock_upgrade_authority(owner, ctx.bumps.owner_ctx.upgrade_lock)?;
+
+    let clock = Clock::get()?;
+    let buffer_hash = buffer_hash(&ctx.accounts.buffer)?;
+    let executable_at = clock.unix_timestamp + i64::from(ctx.accounts.timelock.delay);
+    ctx.accounts.proposal.set_inner(UpgradeProposalState {
+        buffer: ctx.accounts.buffer.key(),
+        buffer_hash,
+        executable_at,
+    });
+
+    emit_cpi!(UpgradeProposed {
+        buffer: ctx.accounts.buffer.key(),
+        buffer_hash,
+        executable_at,
+        signer: owner.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteUpgrade<'info> {
+    /// Anyone can execute an upgrade once its delay has elapsed.
+    pub signer: Signer<'info>,
+
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    /// CHECK: the owner gets the rent of the proposal and the buffer back.
+    #[account(mut, address = config.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [UpgradeProposalState::SEED_PREFIX],
+        bump
+    )]
+    pub proposal: Account<'info, UpgradeProposalState>,
+
+    /// CHECK: the address is the one of the proposal.
+    #[account(mut, address = proposal.buffer)]
+    pub buffer: UncheckedAccount<'info>,
+
+    /// CHECK: the program itself.
+    #[account(mut, address = crate::ID)]
+    pub upgraded_program: UncheckedAccount<'info>,
+
+    pub owner_ctx: OwnerContext<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn execute_upgrade(ctx: Context<ExecuteUpgrade>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        ctx.accounts.clock.unix_timestamp >= proposal.executable_at,
+        PriceOracleError::UpgradeTimelockNotElapsed
+    );
+    // The buffer can't change while its authority is the upgrade lock, but better safe than sorry:
+    require!(
+        buffer_hash(&ctx.accounts.buffer)? == proposal.buffer_hash,
+        PriceOracleError::BufferHashMismatch
+    );
+
+    let owner = &ctx.accounts.owner;
+    let upgrade_lock = &ctx.accounts.owner_ctx.upgrade_lock;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_PREFIX_UPGRADE_LOCK,
+        &[ctx.bumps.owner_ctx.upgrade_lock],
+    ]];
+
+    invoke_signed(
+        &bpf_loader_upgradeable::upgrade(
+            ctx.program_id,
+            &ctx.accounts.buffer.key(),
+            &upgrade_lock.key(),
+            &owner.key(),
+        ),
+        &[
+            ctx.accounts.owner_ctx.program_data.to_account_info(),
+            ctx.accounts.upgraded_program.to_account_info(),
+            ctx.accounts.buffer.to_account_info(),
+            owner.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            upgrade_lock.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    emit_cpi!(UpgradeExecuted {
+        buffer: proposal.buffer,
+        buffer_hash: proposal.buffer_hash,
+        signer: ctx.accounts.signer.key(),
+        slot: ctx.accounts.clock.slot,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelUpgrade<'info> {
+    /// The signer must be the owner.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Program Config account. This program requires that the [`owner`] specified
+    /// in the context equals the owner role stored in the config.
+    #[account(has_one = owner @ PriceOracleError::OwnerOnly)]
+    pub config: Account<'info, PriceOracleConfigState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [UpgradeProposalState::SEED_PREFIX],
+        bump
+    )]
+    pub proposal: Account<'info, UpgradeProposalState>,
+
+    /// CHECK: the address is the one of the proposal.
+    #[account(mut, address = proposal.buffer)]
+    pub buffer: UncheckedAccount<'info>,
+
+    pub owner_ctx: OwnerContext<'info>,
+}
+
+/// Gives the buffer authority back to the owner.
+pub fn cancel_upgrade(ctx: Context<CancelUpgrade>) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let upgrade_lock = &ctx.accounts.owner_ctx.upgrade_lock;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_PREFIX_UPGRADE_LOCK,
+        &[ctx.bumps.owner_ctx.upgrade_lock],
+    ]];
+
+    invoke_signed(
+        &bpf_loader_upgradeable::set_buffer_authority(
+            &ctx.accounts.buffer.key(),
+            &upgrade_lock.key(),
+            &owner.key(),
+        ),
+        &[
+            ctx.accounts.buffer.to_account_info(),
+            upgrade_lock.to_account_info(),
+            owner.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    emit_cpi!(UpgradeCancelled {
+        buffer: ctx.accounts.proposal.buffer,
+        signer: owner.key(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+/// The SHA-256 hash of the program in a buffer, excluding the buffer metadata.
+fn buffer_hash(buffer: &AccountInfo) -> Result<[u8; 32]> {
+    let data = buffer.try_borrow_data()?;
+    let program = data
+        .get(UpgradeableLoaderState::size_of_buffer_metadata()..)
+        .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+
+    Ok(hash(program).to_bytes())
+}
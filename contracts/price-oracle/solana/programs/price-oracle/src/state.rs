@@ -6,3 +6,18 @@ pub use config::*;
 
 pub mod prices;
 pub use prices::*;
+
+pub mod price_history;
+pub use price_history::*;
+
+pub mod scope;
+pub use scope::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod upgrade;
+pub use upgrade::*;
+
+pub mod multisig;
+pub use multisig::*;
@@ -1,22 +1,134 @@
 use anchor_lang::prelude::*;
+use std::io;
 
 /// A badge indicating that an admin account is authorized.
-#[account]
-#[derive(InitSpace)]
+#[derive(AnchorSerialize, Clone, InitSpace)]
 pub struct AuthBadgeState {
     pub address: Pubkey,
     pub is_admin: bool,
+
+    /// Unix timestamp after which the badge no longer grants any role.
+    /// Zero means that the badge never expires.
+    pub expires_at: i64,
+
+    /// Who paid for the badge account, and gets the rent back when an expired badge is closed.
+    /// Badges created before expiration was introduced have a default payer and can only be
+    /// removed by the owner or an admin.
+    pub payer: Pubkey,
 }
 
 impl AuthBadgeState {
     /// Value `b"authbadge"`.
     pub const SEED_PREFIX: &'static [u8] = b"authbadge";
 
+    /// Size of a badge created before `expires_at` and `payer` were added.
+    const LEGACY_SIZE: usize = 32 /* address */ + 1 /* is_admin */;
+
     pub fn is_admin(&self, account: &impl Key) -> bool {
-        self.address == account.key() && self.is_admin
+        self.address == account.key() && self.is_admin && !self.is_expired()
     }
 
     pub fn is_assistant(&self, account: &impl Key) -> bool {
-        self.address == account.key()
+        self.address == account.key() && !self.is_expired()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        // Outside of the runtime there is no clock, so the badge is considered expired:
+        self.expires_at != 0
+            && Clock::get().map_or(true, |clock| self.expires_at <= clock.unix_timestamp)
+    }
+}
+
+impl Owner for AuthBadgeState {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl Discriminator for AuthBadgeState {
+    // Equals sha256("account:AuthBadgeState")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[0xE6, 0x88, 0x80, 0x75, 0xD6, 0x33, 0xD6, 0x98];
+}
+
+impl AccountSerialize for AuthBadgeState {
+    fn try_serialize<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(Self::DISCRIMINATOR)?;
+        self.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for AuthBadgeState {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < Self::DISCRIMINATOR.len() {
+            return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        let discriminator = &(*buf)[..8];
+        if discriminator != Self::DISCRIMINATOR {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        Self::deserialize_reader(&mut &(*buf)[8..]).map_err(|e| e.into())
+    }
+}
+
+impl AnchorDeserialize for AuthBadgeState {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut data = Vec::with_capacity(Self::INIT_SPACE);
+        reader.read_to_end(&mut data)?;
+        let is_legacy = data.len() == Self::LEGACY_SIZE;
+        let mut data = data.as_slice();
+
+        let address = Pubkey::deserialize(&mut data)?;
+        let is_admin = bool::deserialize(&mut data)?;
+
+        if is_legacy {
+            return Ok(Self {
+                address,
+                is_admin,
+                expires_at: 0,
+                payer: Pubkey::default(),
+            });
+        }
+
+        Ok(Self {
+            address,
+            is_admin,
+            expires_at: i64::deserialize(&mut data)?,
+            payer: Pubkey::deserialize(&mut data)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_badges_never_expire() {
+        let address = Pubkey::new_unique();
+        let mut data = AuthBadgeState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(address.as_ref());
+        data.push(1);
+        assert_eq!(data.len(), 8 + AuthBadgeState::LEGACY_SIZE);
+
+        let badge = AuthBadgeState::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(badge.address, address);
+        assert!(badge.is_admin);
+        assert_eq!(badge.expires_at, 0);
+        assert_eq!(badge.payer, Pubkey::default());
+        assert!(!badge.is_expired());
+
+        let mut serialized = Vec::new();
+        badge.try_serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), 8 + AuthBadgeState::INIT_SPACE);
+        let badge = AuthBadgeState::try_deserialize(&mut serialized.as_slice()).unwrap();
+        assert_eq!(badge.address, address);
+        assert_eq!(badge.expires_at, 0);
     }
 }
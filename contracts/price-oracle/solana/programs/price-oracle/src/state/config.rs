@@ -1,5 +1,9 @@
-use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_lang::{prelude::*, ZeroCopy};
 use crate::{int::Int, utils::int_to_u64};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "idl-build")]
+use anchor_lang::{idl::types::IdlTypeDef, IdlBuild};
+use fee_math::prices::{lamports_to_micro_usd, micro_usd_to_lamports, usd18_to_lamports};
 
 #[account]
 #[derive(InitSpace)]
@@ -21,15 +25,105 @@ impl PriceOracleConfigState {
     }
 
     pub fn micro_usd_to_sol(&self, micro_usd: u64) -> Result<u64> {
-        // μusd * lamports/SOL / μusd/SOL
-        int_to_u64(Int::Ok(micro_usd) * LAMPORTS_PER_SOL / self.sol_price)
+        int_to_u64(micro_usd_to_lamports(micro_usd, self.sol_price))
+    }
+
+    pub fn usd18_to_sol(&self, usd18: Int<u128>) -> Result<u64> {
+        int_to_u64(usd18_to_lamports(usd18, self.sol_price))
     }
 
     pub fn sol_to_micro_usd(&self, lamports: u64) -> Result<u64> {
-        // lamports * μusd/SOL / lamports/SOL
-        int_to_u64(Int::Ok(lamports) * self.sol_price / LAMPORTS_PER_SOL)
+        int_to_u64(lamports_to_micro_usd(lamports, self.sol_price))
     }
 
     /// AKA `b"config"`.
     pub const SEED_PREFIX: &'static [u8; 6] = b"config";
 }
+
+/// The account layout of [`PriceOracleConfigState`], so that readers of the SOL price don't have
+/// to deserialize it, i.e. via `AccountLoader<'info, ZeroCopyPriceOracleConfigState>`.
+///
+/// The account always has room for a pending owner, but without one Borsh writes the SOL price
+/// right after the `None` tag, so its offset depends on the tag.
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ZeroCopyPriceOracleConfigState {
+    owner: Pubkey,
+    pending_owner_tag: u8,
+    pending_owner: Pubkey,
+    sol_price: [u8; 8],
+}
+
+impl ZeroCopyPriceOracleConfigState {
+    pub fn owner(&self) -> Pubkey {
+        self.owner
+    }
+
+    /// The SOL price in μusd/SOL.
+    pub fn sol_price(&self) -> u64 {
+        let mut sol_price = self.sol_price;
+        if self.pending_owner_tag == 0 {
+            sol_price.copy_from_slice(&self.pending_owner.as_ref()[..8]);
+        }
+        u64::from_le_bytes(sol_price)
+    }
+
+    pub fn micro_usd_to_sol(&self, micro_usd: u64) -> Result<u64> {
+        int_to_u64(micro_usd_to_lamports(micro_usd, self.sol_price()))
+    }
+
+    pub fn sol_to_micro_usd(&self, lamports: u64) -> Result<u64> {
+        int_to_u64(lamports_to_micro_usd(lamports, self.sol_price()))
+    }
+}
+
+impl ZeroCopy for ZeroCopyPriceOracleConfigState {}
+
+impl Owner for ZeroCopyPriceOracleConfigState {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl Discriminator for ZeroCopyPriceOracleConfigState {
+    const DISCRIMINATOR: &'static [u8] = PriceOracleConfigState::DISCRIMINATOR;
+}
+
+/// Clients see the same account as when it's deserialized.
+#[cfg(feature = "idl-build")]
+impl IdlBuild for ZeroCopyPriceOracleConfigState {
+    fn create_type() -> Option<IdlTypeDef> {
+        PriceOracleConfigState::create_type()
+    }
+
+    fn insert_types(types: &mut std::collections::BTreeMap<String, IdlTypeDef>) {
+        PriceOracleConfigState::insert_types(types)
+    }
+}
+
+#[test]
+fn zero_copy_layout_matches_the_account() {
+    assert_eq!(
+        std::mem::size_of::<ZeroCopyPriceOracleConfigState>(),
+        PriceOracleConfigState::INIT_SPACE
+    );
+
+    let owner = Pubkey::new_unique();
+    for pending_owner in [None, Some(Pubkey::new_unique())] {
+        let config = PriceOracleConfigState {
+            owner,
+            pending_owner,
+            sol_price: 150_000_000,
+        };
+        let mut data = vec![0; 8 + PriceOracleConfigState::INIT_SPACE];
+        config.try_serialize(&mut data.as_mut_slice()).unwrap();
+
+        let zero_copy: &ZeroCopyPriceOracleConfigState = bytemuck::from_bytes(&data[8..]);
+        assert_eq!(zero_copy.owner(), owner);
+        assert_eq!(zero_copy.sol_price(), 150_000_000);
+        assert_eq!(
+            zero_copy.micro_usd_to_sol(1_500_000).unwrap(),
+            config.micro_usd_to_sol(1_500_000).unwrap()
+        );
+    }
+}
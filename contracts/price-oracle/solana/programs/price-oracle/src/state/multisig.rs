@@ -0,0 +1,237 @@
+use crate::error::PriceOracleError;
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+
+/// The maximum number of signers of a multisig.
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// Why a proposal can't be approved nor executed anymore, in which case it can only be cancelled.
+///
+/// Also used by the multisig of the CCTPR program, so that both follow the same rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaleProposal {
+    /// The signers or the threshold of the multisig changed since the proposal was made.
+    Outdated,
+    /// The lifetime of the proposal elapsed.
+    Expired,
+}
+
+impl StaleProposal {
+    /// How long a proposal can be approved and executed, in seconds.
+    pub const LIFETIME: i64 = 7 * 24 * 60 * 60;
+
+    /// Checks a proposal made for the `proposal_generation` of a multisig, see
+    /// [`MultisigState::generation`].
+    pub fn check(
+        proposal_generation: u64,
+        expires_at: i64,
+        multisig_generation: u64,
+        now: i64,
+    ) -> std::result::Result<(), Self> {
+        if proposal_generation != multisig_generation {
+            Err(Self::Outdated)
+        } else if now > expires_at {
+            Err(Self::Expired)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An M-of-N multisig that can act as the program owner.
+///
+/// The multisig itself doesn't sign anything: its authority, a data-less PDA derived from it (see
+/// [`MultisigState::authority`]), is what gets set as the owner. Once a proposal has collected
+/// `threshold` approvals, its instruction is invoked on this program with the authority as signer.
+/// Since the authority also pays for the accounts created by the owner, it has to be funded.
+#[account]
+#[derive(InitSpace)]
+pub struct MultisigState {
+    /// Arbitrary key making the address unique, so that several multisigs can coexist.
+    pub create_key: Pubkey,
+
+    #[max_len(MAX_MULTISIG_SIGNERS)]
+    pub signers: Vec<Pubkey>,
+
+    /// How many of the `signers` must approve a proposal before it can be executed.
+    pub threshold: u8,
+
+    /// The index of the next proposal.
+    pub proposal_count: u64,
+
+    /// Incremented whenever the signers or the threshold change, which invalidates all pending
+    /// proposals.
+    pub generation: u64,
+}
+
+impl MultisigState {
+    /// Value `b"multisig"`.
+    pub const SEED_PREFIX: &'static [u8] = b"multisig";
+
+    /// Value `b"multisig_authority"`.
+    pub const AUTHORITY_SEED_PREFIX: &'static [u8] = b"multisig_authority";
+
+    /// The address acting on behalf of the multisig, along with its bump.
+    pub fn authority(multisig: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::AUTHORITY_SEED_PREFIX, multisig.as_ref()],
+            &crate::ID,
+        )
+    }
+
+    pub fn is_signer(&self, account: &impl Key) -> bool {
+        self.signers.contains(&account.key())
+    }
+
+    /// Whether `signers` and `threshold` make up a usable multisig.
+    pub fn is_valid(signers: &[Pubkey], threshold: u8) -> bool {
+        let unique = signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| !signers[..i].contains(signer));
+
+        unique
+            && signers.len() <= MAX_MULTISIG_SIGNERS
+            && threshold != 0
+            && usize::from(threshold) <= signers.len()
+    }
+}
+
+/// An account of a proposed instruction, see [`AccountMeta`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposedAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An instruction of this program proposed to a multisig.
+#[account]
+pub struct MultisigProposalState {
+    pub multisig: Pubkey,
+
+    /// The index of the proposal within the multisig, used to derive its address.
+    pub index: u64,
+
+    /// Gets the rent back once the proposal is executed or cancelled.
+    pub proposer: Pubkey,
+
+    /// The generation of the multisig the proposal was made for, see
+    /// [`MultisigState::generation`].
+    pub generation: u64,
+
+    /// Afterwards, the proposal can only be cancelled, which anyone can do.
+    pub expires_at: i64,
+
+    pub accounts: Vec<ProposedAccountMeta>,
+    pub data: Vec<u8>,
+
+    /// The signers who approved the proposal so far.
+    pub approvals: Vec<Pubkey>,
+}
+
+impl MultisigProposalState {
+    /// Value `b"multisig_proposal"`.
+    pub const SEED_PREFIX: &'static [u8] = b"multisig_proposal";
+
+    /// The account size (discriminator included) of a proposal.
+    pub fn space(account_count: usize, data_len: usize) -> usize {
+        8 + 32 /* multisig */
+            + 8 /* index */
+            + 32 /* proposer */
+            + 8 /* generation */
+            + 8 /* expires_at */
+            + 4 + account_count * (32 + 1 + 1) /* accounts */
+            + 4 + data_len /* data */
+            + 4 + MAX_MULTISIG_SIGNERS * 32 /* approvals */
+    }
+
+    /// Whether the proposal can still be approved and executed.
+    pub fn check_pending(&self, multisig: &MultisigState, now: i64) -> Result<()> {
+        StaleProposal::check(self.generation, self.expires_at, multisig.generation, now)
+            .map_err(|stale| PriceOracleError::from(stale).into())
+    }
+
+    /// Approvals of accounts that are no longer signers of the multisig don't count.
+    pub fn approval_count(&self, multisig: &MultisigState) -> usize {
+        self.approvals
+            .iter()
+            .filter(|approval| multisig.is_signer(*approval))
+            .count()
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[test]
+fn multisig_approvals_are_counted_for_current_signers_only() {
+    let signers = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    assert!(MultisigState::is_valid(&signers, 2));
+    assert!(!MultisigState::is_valid(&signers, 0));
+    assert!(!MultisigState::is_valid(&signers, 4));
+    assert!(!MultisigState::is_valid(&[signers[0], signers[0]], 1));
+    let too_many: Vec<_> = (0..=MAX_MULTISIG_SIGNERS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    assert!(!MultisigState::is_valid(&too_many, 1));
+
+    let mut multisig = MultisigState {
+        create_key: Pubkey::new_unique(),
+        signers: signers.to_vec(),
+        threshold: 2,
+        proposal_count: 0,
+        generation: 0,
+    };
+    let now = 1_700_000_000;
+    let proposal = MultisigProposalState {
+        multisig: Pubkey::new_unique(),
+        index: 0,
+        proposer: signers[0],
+        generation: 0,
+        expires_at: now + StaleProposal::LIFETIME,
+        accounts: vec![],
+        data: vec![1, 2, 3],
+        approvals: vec![signers[0], signers[1]],
+    };
+    assert_eq!(proposal.approval_count(&multisig), 2);
+    assert_eq!(
+        MultisigProposalState::space(0, 3),
+        8 + proposal.try_to_vec().unwrap().len() + (MAX_MULTISIG_SIGNERS - 2) * 32,
+    );
+
+    assert!(proposal.check_pending(&multisig, now).is_ok());
+    assert_eq!(
+        proposal
+            .check_pending(&multisig, proposal.expires_at + 1)
+            .unwrap_err(),
+        PriceOracleError::ProposalExpired.into(),
+    );
+
+    // The second signer is replaced:
+    multisig.signers[1] = Pubkey::new_unique();
+    assert_eq!(proposal.approval_count(&multisig), 1);
+
+    // Changing the signers invalidates pending proposals:
+    multisig.generation += 1;
+    assert_eq!(
+        proposal.check_pending(&multisig, now).unwrap_err(),
+        PriceOracleError::ProposalOutdated.into(),
+    );
+}
@@ -0,0 +1,263 @@
+use crate::state::{PricesState, PricesStatePlatform};
+use anchor_lang::prelude::*;
+
+/// The number of observations kept per chain.
+pub const PRICE_HISTORY_CAPACITY: usize = 32;
+
+/// A snapshot of the prices of a chain, independent of its platform.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+pub struct PriceObservation {
+    /// Unix timestamp of the update.
+    pub timestamp: i64,
+
+    /// The gas token price in μusd/Token, with the precision of the prices.
+    pub gas_token_price: u64,
+
+    /// EVM: `gas_price`, Sui: `computation_unit_price`.
+    pub execution_price: u32,
+
+    /// EVM: `price_per_tx_byte`, Sui: `byte_price`.
+    pub byte_price: u32,
+
+    /// Sui only: `rebate_ratio`.
+    pub rebate_ratio: u8,
+}
+
+impl PriceObservation {
+    pub fn new(prices: &PricesState, timestamp: i64) -> Self {
+        let (execution_price, byte_price, rebate_ratio) = match prices.prices {
+            PricesStatePlatform::Evm {
+                gas_price,
+                price_per_tx_byte,
+            } => (gas_price, price_per_tx_byte, 0),
+            PricesStatePlatform::Sui {
+                computation_unit_price,
+                byte_price,
+                rebate_ratio,
+            } => (computation_unit_price, byte_price, rebate_ratio),
+            PricesStatePlatform::Uninitialized(_) => (0, 0, 0),
+        };
+
+        Self {
+            timestamp,
+            gas_token_price: prices.gas_token_price,
+            execution_price,
+            byte_price,
+            rebate_ratio,
+        }
+    }
+}
+
+impl PricesState {
+    /// Returns a copy of these prices with the values of the given observation, e.g. to quote
+    /// with a smoothed price instead of the spot one.
+    pub fn with_observation(&self, observation: &PriceObservation) -> Self {
+        let prices = match self.prices {
+            PricesStatePlatform::Evm { .. } => PricesStatePlatform::Evm {
+                gas_price: observation.execution_price,
+                price_per_tx_byte: observation.byte_price,
+            },
+            PricesStatePlatform::Sui { .. } => PricesStatePlatform::Sui {
+                computation_unit_price: observation.execution_price,
+                byte_price: observation.byte_price,
+                rebate_ratio: observation.rebate_ratio,
+            },
+            uninitialized @ PricesStatePlatform::Uninitialized(_) => uninitialized,
+        };
+
+        Self {
+            chain_id: self.chain_id,
+            gas_token_price: observation.gas_token_price,
+            prices,
+            precision: self.precision,
+        }
+    }
+}
+
+/// Ring buffer of the most recent price updates of a chain.
+#[account]
+#[derive(InitSpace)]
+pub struct PriceHistoryState {
+    /// Same as the chain id of the corresponding [`PricesState`].
+    pub chain_id: u16,
+
+    /// The slot of the next observation, i.e. the one that will be overwritten next.
+    pub next_index: u8,
+
+    /// The number of valid observations, at most [`PRICE_HISTORY_CAPACITY`].
+    pub count: u8,
+
+    pub observations: [PriceObservation; PRICE_HISTORY_CAPACITY],
+}
+
+impl PriceHistoryState {
+    /// Value `b"price_history"`.
+    pub const SEED_PREFIX: &'static [u8] = b"price_history";
+
+    pub fn record(&mut self, observation: PriceObservation) {
+        self.observations[usize::from(self.next_index)] = observation;
+        self.next_index = ((usize::from(self.next_index) + 1) % PRICE_HISTORY_CAPACITY) as u8;
+        self.count = (usize::from(self.count) + 1).min(PRICE_HISTORY_CAPACITY) as u8;
+    }
+
+    /// The valid observations, from the oldest to the most recent one.
+    pub fn iter(&self) -> impl Iterator<Item = &PriceObservation> {
+        let count = usize::from(self.count);
+        let first = (usize::from(self.next_index) + PRICE_HISTORY_CAPACITY - count)
+            % PRICE_HISTORY_CAPACITY;
+        (0..count).map(move |i| &self.observations[(first + i) % PRICE_HISTORY_CAPACITY])
+    }
+
+    pub fn latest(&self) -> Option<&PriceObservation> {
+        self.iter().last()
+    }
+
+    /// Time-weighted average of each price over the last `window` seconds before `now`.
+    ///
+    /// Every observation is weighted by how long it was the current one within the window. The
+    /// observation that was current at the start of the window counts from the start of it.
+    /// Returns the latest observation if the window is empty and `None` if there are no
+    /// observations at all.
+    pub fn twap(&self, now: i64, window: u32) -> Option<PriceObservation> {
+        let latest = *self.latest()?;
+        let window_start = now.saturating_sub(window.into());
+
+        let mut sums = [0_u128; 4];
+        let mut total_weight = 0_u128;
+        let observations = self.iter().collect::<Vec<_>>();
+        for (i, observation) in observations.iter().enumerate() {
+            let end = observations.get(i + 1).map_or(now, |next| next.timestamp);
+            let start = observation.timestamp.max(window_start);
+            if end <= start {
+                continue;
+            }
+
+            let weight = (end - start) as u128;
+            for (sum, value) in sums.iter_mut().zip(observation.values()) {
+                *sum += value * weight;
+            }
+            total_weight += weight;
+        }
+
+        if total_weight == 0 {
+            return Some(latest);
+        }
+
+        let [gas_token_price, execution_price, byte_price, rebate_ratio] =
+            sums.map(|sum| sum / total_weight);
+        Some(PriceObservation {
+            timestamp: now,
+            gas_token_price: gas_token_price as u64,
+            execution_price: execution_price as u32,
+            byte_price: byte_price as u32,
+            rebate_ratio: rebate_ratio as u8,
+        })
+    }
+
+    /// The (lower) median of each price across all observations, independently of each other.
+    pub fn median(&self) -> Option<PriceObservation> {
+        let latest = *self.latest()?;
+        let median_of = |value: fn(&PriceObservation) -> u64| {
+            let mut values = self.iter().map(value).collect::<Vec<_>>();
+            values.sort_unstable();
+            values[(values.len() - 1) / 2]
+        };
+
+        Some(PriceObservation {
+            timestamp: latest.timestamp,
+            gas_token_price: median_of(|o| o.gas_token_price),
+            execution_price: median_of(|o| o.execution_price.into()) as u32,
+            byte_price: median_of(|o| o.byte_price.into()) as u32,
+            rebate_ratio: median_of(|o| o.rebate_ratio.into()) as u8,
+        })
+    }
+}
+
+impl PriceObservation {
+    fn values(&self) -> [u128; 4] {
+        [
+            self.gas_token_price.into(),
+            self.execution_price.into(),
+            self.byte_price.into(),
+            self.rebate_ratio.into(),
+        ]
+    }
+}
+
+#[cfg(test)]
+fn observation(timestamp: i64, gas_token_price: u64) -> PriceObservation {
+    PriceObservation {
+        timestamp,
+        gas_token_price,
+        execution_price: 10,
+        byte_price: 20,
+        rebate_ratio: 95,
+    }
+}
+
+#[test]
+fn price_history_wraps_around() {
+    let mut history = PriceHistoryState {
+        chain_id: crate::utils::ETHEREUM_CHAIN_ID,
+        next_index: 0,
+        count: 0,
+        observations: [PriceObservation::default(); PRICE_HISTORY_CAPACITY],
+    };
+    assert!(history.latest().is_none());
+    assert!(history.twap(100, 60).is_none());
+    assert!(history.median().is_none());
+
+    for i in 0..PRICE_HISTORY_CAPACITY as i64 + 5 {
+        history.record(observation(i, i as u64));
+    }
+
+    assert_eq!(usize::from(history.count), PRICE_HISTORY_CAPACITY);
+    let timestamps = history.iter().map(|o| o.timestamp).collect::<Vec<_>>();
+    assert_eq!(
+        timestamps,
+        (5..PRICE_HISTORY_CAPACITY as i64 + 5).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        history.latest().unwrap().timestamp,
+        PRICE_HISTORY_CAPACITY as i64 + 4
+    );
+}
+
+#[test]
+fn twap_and_median_dampen_outliers() {
+    let mut history = PriceHistoryState {
+        chain_id: crate::utils::ETHEREUM_CHAIN_ID,
+        next_index: 0,
+        count: 0,
+        observations: [PriceObservation::default(); PRICE_HISTORY_CAPACITY],
+    };
+    history.record(observation(0, 1_000));
+    history.record(observation(100, 2_000));
+    history.record(observation(150, 1_000));
+    // A bad update that's immediately corrected:
+    history.record(observation(190, 1_000_000));
+    history.record(observation(200, 1_000));
+
+    // 50s of 2_000, 40s of 1_000, 10s of 1_000_000 and 0s of 1_000:
+    let twap = history.twap(200, 100).unwrap();
+    assert_eq!(
+        twap.gas_token_price,
+        (50 * 2_000 + 40 * 1_000 + 10 * 1_000_000) / 100
+    );
+    assert_eq!(twap.execution_price, 10);
+    assert_eq!(twap.rebate_ratio, 95);
+
+    // The observation that was current at the start of the window counts from the start of it:
+    let twap = history.twap(300, 250).unwrap();
+    assert_eq!(
+        twap.gas_token_price,
+        (50 * 1_000 + 50 * 2_000 + 40 * 1_000 + 10 * 1_000_000 + 100 * 1_000) / 250,
+    );
+
+    // An empty window falls back to the latest observation:
+    assert_eq!(history.twap(200, 0).unwrap().gas_token_price, 1_000);
+
+    assert_eq!(history.median().unwrap().gas_token_price, 1_000);
+}
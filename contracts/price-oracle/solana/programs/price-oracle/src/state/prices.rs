@@ -1,5 +1,7 @@
-use crate::{int::Int, utils::{Platform, int_to_u64}};
-use anchor_lang::prelude::*;
+use crate::{error::PriceOracleError, int::Int, utils::{Platform, int_to_u64}};
+use fee_math::prices::{ChainPrices, PlatformPrices, TargetChainCosts};
+use anchor_lang::{prelude::*, ZeroCopy};
+use bytemuck::{Pod, Zeroable};
 #[cfg(feature = "idl-build")]
 use anchor_lang::{
     IdlBuild,
@@ -11,12 +13,7 @@ use std::io;
 
 use super::PriceOracleConfigState;
 
-const SUI_MIN_TRANSACTION_COST_MIST: u64 = 2_000;
-
-const MICRO_GAS_TOKEN_PER_GAS_TOKEN: u64 = 1_000_000;
-const MWEI_PER_MICRO_ETH: u64 = 1_000_000;
-const MWEI_PER_ETH: u64 = 1_000_000_000_000;
-const MIST_PER_SUI: u64 = 1_000_000_000;
+pub use fee_math::prices::{MAX_PRICE_PRECISION, USD18_PER_MICRO_USD};
 
 /// Chain prices.
 #[derive(Clone, Copy, Debug)]
@@ -30,6 +27,12 @@ pub struct PricesState {
 
     /// The prices for the chain, in µToken.
     pub prices: PricesStatePlatform,
+
+    /// How many extra decimals all the prices of the chain have, e.g. with a precision of 3
+    /// `gas_token_price` is in 10⁻⁹usd/Token and an EVM `gas_price` in Kwei/gas.
+    /// Stored in the last byte of the account, which is zero for accounts created before the
+    /// precision was introduced.
+    pub precision: u8,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -55,13 +58,14 @@ pub enum PricesStatePlatform {
 }
 
 impl PricesStatePlatform {
-    const SIZE: usize = 16;
+    const SIZE: usize = 15;
 }
 
 impl Space for PricesState {
     const INIT_SPACE: usize = std::mem::size_of::<u16>() /* chain_id */
         + std::mem::size_of::<u64>() /* gas_token_price */
-        + PricesStatePlatform::SIZE;
+        + PricesStatePlatform::SIZE
+        + std::mem::size_of::<u8>() /* precision */;
 }
 
 impl Owner for PricesState {
@@ -70,38 +74,118 @@ impl Owner for PricesState {
     }
 }
 
+/// The platform-specific prices share the same 15 bytes, and which layout applies depends on
+/// `chain_id`. Borsh enums are prefixed by their variant, which the account doesn't store, so the
+/// IDL describes `prices` as the union of both layouts instead: each field holds the price at its
+/// offset for either platform, and the ones the platform doesn't use are zero.
 #[cfg(feature = "idl-build")]
 impl IdlBuild for PricesState {
     fn create_type() -> Option<IdlTypeDef> {
-        Some(IdlTypeDef {
-            name: "PricesState".to_string(),
-            docs: vec![],
-            serialization: IdlSerialization::Borsh,
-            repr: None,
-            generics: vec![],
-            ty: IdlTypeDefTy::Struct {
-                fields: Some(IdlDefinedFields::Named(vec![
-                    IdlField {
-                        name: "chain_id".to_string(),
-                        docs: vec![],
-                        ty: IdlType::U16,
-                    },
-                    IdlField {
-                        name: "gas_token_price".to_string(),
-                        docs: vec![],
-                        ty: IdlType::U64,
+        Some(idl_type_def(
+            "PricesState",
+            vec!["Chain prices.".to_string()],
+            idl_fields(vec![
+                idl_field("chain_id", "0 if the prices are uninitialized.", IdlType::U16),
+                idl_field(
+                    "gas_token_price",
+                    "In μusd/Token, scaled by `precision`.",
+                    IdlType::U64,
+                ),
+                idl_field(
+                    "prices",
+                    PRICES_SELECTION_DOC,
+                    IdlType::Defined {
+                        name: "PricesStatePlatform".to_string(),
+                        generics: vec![],
                     },
-                    IdlField {
-                        name: "prices".to_string(),
-                        docs: vec![],
-                        ty: IdlType::Array(Box::new(IdlType::U8), IdlArrayLen::Value(16)),
-                    },
-                ])),
-            },
-        })
+                ),
+                idl_field(
+                    "precision",
+                    "Extra decimals of all the prices.",
+                    IdlType::U8,
+                ),
+            ]),
+        ))
+    }
+
+    fn insert_types(types: &mut std::collections::BTreeMap<String, IdlTypeDef>) {
+        let platform = idl_type_def(
+            "PricesStatePlatform",
+            vec![PRICES_SELECTION_DOC.to_string()],
+            idl_fields(vec![
+                idl_field(
+                    "gas_price",
+                    "EVM: the gas price in Mwei/gas. Sui: the computation unit price in MIST/CU.",
+                    IdlType::U32,
+                ),
+                idl_field(
+                    "byte_price",
+                    "EVM: the price per transaction byte in Mwei/byte. Sui: the byte price in \
+                     MIST/byte.",
+                    IdlType::U32,
+                ),
+                idl_field(
+                    "rebate_ratio",
+                    "Sui only: the rebated percentage of the storage cost.",
+                    IdlType::U8,
+                ),
+                idl_padding(PricesStatePlatform::SIZE - 4 - 4 - 1),
+            ]),
+        );
+        types.insert(platform.name.clone(), platform);
     }
 }
 
+/// Which platform the `prices` are for, see `Platform::from_chain_id`.
+#[cfg(feature = "idl-build")]
+const PRICES_SELECTION_DOC: &str = "Platform-specific prices: Sui prices if `chain_id` is 21 \
+    (Sui), EVM prices for any other chain id but 0 (uninitialized).";
+#[cfg(feature = "idl-build")]
+const _: () = assert!(crate::utils::SUI_CHAIN_ID == 21, "update the IDL docs");
+
+#[cfg(feature = "idl-build")]
+fn idl_type_def(name: &str, docs: Vec<String>, ty: IdlTypeDefTy) -> IdlTypeDef {
+    IdlTypeDef {
+        name: name.to_string(),
+        docs,
+        serialization: IdlSerialization::Borsh,
+        repr: None,
+        generics: vec![],
+        ty,
+    }
+}
+
+#[cfg(feature = "idl-build")]
+fn idl_fields(fields: Vec<IdlField>) -> IdlTypeDefTy {
+    IdlTypeDefTy::Struct {
+        fields: Some(IdlDefinedFields::Named(fields)),
+    }
+}
+
+#[cfg(feature = "idl-build")]
+fn idl_field(name: &str, doc: &str, ty: IdlType) -> IdlField {
+    IdlField {
+        name: name.to_string(),
+        docs: if doc.is_empty() {
+            vec![]
+        } else {
+            vec![doc.to_string()]
+        },
+        ty,
+    }
+}
+
+/// Keeps the `prices` layout [`PricesStatePlatform::SIZE`] bytes long, so that `precision` is
+/// decoded from the right offset.
+#[cfg(feature = "idl-build")]
+fn idl_padding(len: usize) -> IdlField {
+    idl_field(
+        "padding",
+        "",
+        IdlType::Array(Box::new(IdlType::U8), IdlArrayLen::Value(len)),
+    )
+}
+
 impl Discriminator for PricesState {
     // Equals sha256("account:PricesState")[..8]
     const DISCRIMINATOR: &'static [u8] = &[0x37, 0x89, 0x31, 0xBB, 0x0F, 0x63, 0x01, 0x1E];
@@ -123,7 +207,12 @@ impl AccountDeserialize for PricesState {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
 
-        Self::try_deserialize_unchecked(buf)
+        // Only `init` deserializes through the unchecked path, everywhere else the prices must
+        // have been registered:
+        let prices = Self::try_deserialize_unchecked(buf)?;
+        require!(prices.chain_id != 0, PriceOracleError::UninitializedPrices);
+
+        Ok(prices)
     }
 
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
@@ -133,56 +222,131 @@ impl AccountDeserialize for PricesState {
 
 impl AnchorSerialize for PricesState {
     fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        self.chain_id.serialize(writer)?;
-        self.gas_token_price.serialize(writer)?;
-        self.prices.serialize(writer)?;
-        Ok(())
+        writer.write_all(bytemuck::bytes_of(&ZeroCopyPricesState::from(self)))
     }
 }
 
 impl AnchorDeserialize for PricesState {
     fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
-        let chain_id = u16::deserialize_reader(reader)?;
-        let gas_token_price = u64::deserialize_reader(reader)?;
-        let prices = PricesStatePlatform::deserialize_reader(reader, chain_id)?;
+        let mut data = ZeroCopyPricesState::zeroed();
+        reader.read_exact(bytemuck::bytes_of_mut(&mut data))?;
+        PricesState::try_from(&data)
+    }
+}
+
+/// The account layout of [`PricesState`], so that readers can access the prices without
+/// deserializing them, i.e. via `AccountLoader<'info, ZeroCopyPricesState>`.
+///
+/// All fields are byte arrays, so there is no padding and the layout matches the one of the
+/// existing accounts byte for byte.
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ZeroCopyPricesState {
+    chain_id: [u8; 2],
+    gas_token_price: [u8; 8],
+    prices: [u8; PricesStatePlatform::SIZE],
+    precision: u8,
+}
+
+impl ZeroCopyPricesState {
+    pub fn chain_id(&self) -> u16 {
+        u16::from_le_bytes(self.chain_id)
+    }
+
+    /// The decoded prices, which must have been registered.
+    pub fn prices_state(&self) -> Result<PricesState> {
+        let prices = PricesState::try_from(self)?;
+        require!(prices.chain_id != 0, PriceOracleError::UninitializedPrices);
+
+        Ok(prices)
+    }
+}
+
+impl ZeroCopy for ZeroCopyPricesState {}
+
+impl Owner for ZeroCopyPricesState {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl Discriminator for ZeroCopyPricesState {
+    const DISCRIMINATOR: &'static [u8] = PricesState::DISCRIMINATOR;
+}
+
+/// Clients see the same account as when it's deserialized.
+#[cfg(feature = "idl-build")]
+impl IdlBuild for ZeroCopyPricesState {
+    fn create_type() -> Option<IdlTypeDef> {
+        PricesState::create_type()
+    }
+
+    fn insert_types(types: &mut std::collections::BTreeMap<String, IdlTypeDef>) {
+        PricesState::insert_types(types)
+    }
+}
+
+impl From<&PricesState> for ZeroCopyPricesState {
+    fn from(prices: &PricesState) -> Self {
+        ZeroCopyPricesState {
+            chain_id: prices.chain_id.to_le_bytes(),
+            gas_token_price: prices.gas_token_price.to_le_bytes(),
+            prices: prices.prices.to_bytes(),
+            precision: prices.precision,
+        }
+    }
+}
+
+impl TryFrom<&ZeroCopyPricesState> for PricesState {
+    type Error = io::Error;
+
+    fn try_from(data: &ZeroCopyPricesState) -> io::Result<Self> {
+        let chain_id = u16::from_le_bytes(data.chain_id);
+        if data.precision > MAX_PRICE_PRECISION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unsupported price precision",
+            ));
+        }
         Ok(PricesState {
             chain_id,
-            gas_token_price,
-            prices,
+            gas_token_price: u64::from_le_bytes(data.gas_token_price),
+            prices: PricesStatePlatform::from_bytes(data.prices, chain_id)?,
+            precision: data.precision,
         })
     }
 }
 
 impl PricesStatePlatform {
-    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    /// Unused bytes are zero, so that the precision has a fixed offset.
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut data = [0; Self::SIZE];
         match self {
-            PricesStatePlatform::Uninitialized(_) => {
-                writer.write_all(&[0; PricesStatePlatform::SIZE])?;
-            }
+            PricesStatePlatform::Uninitialized(_) => {}
             PricesStatePlatform::Evm {
                 gas_price,
                 price_per_tx_byte,
             } => {
-                gas_price.serialize(writer)?;
-                price_per_tx_byte.serialize(writer)?;
+                data[..4].copy_from_slice(&gas_price.to_le_bytes());
+                data[4..8].copy_from_slice(&price_per_tx_byte.to_le_bytes());
             }
             PricesStatePlatform::Sui {
                 computation_unit_price,
                 byte_price,
                 rebate_ratio,
             } => {
-                computation_unit_price.serialize(writer)?;
-                byte_price.serialize(writer)?;
-                rebate_ratio.serialize(writer)?;
+                data[..4].copy_from_slice(&computation_unit_price.to_le_bytes());
+                data[4..8].copy_from_slice(&byte_price.to_le_bytes());
+                data[8] = rebate_ratio;
             }
         }
 
-        Ok(())
+        data
     }
-}
 
-impl PricesStatePlatform {
-    fn deserialize_reader<R: io::Read>(reader: &mut R, chain_id: u16) -> io::Result<Self> {
+    fn from_bytes(data: [u8; Self::SIZE], chain_id: u16) -> io::Result<Self> {
+        let reader = &mut &data[..];
+
         match Platform::from_chain_id(chain_id) {
             Some(Platform::Evm) => {
                 let gas_price = u32::deserialize_reader(reader)?;
@@ -202,7 +366,7 @@ impl PricesStatePlatform {
                     rebate_ratio,
                 })
             }
-            None => Ok(PricesStatePlatform::Uninitialized([0; 16])),
+            None => Ok(PricesStatePlatform::Uninitialized(data)),
             Some(Platform::Sol) => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Solana cannot be registered as a foreign chain",
@@ -214,8 +378,98 @@ impl PricesStatePlatform {
 impl PricesState {
     pub const SEED_PREFIX: &'static [u8] = b"prices";
 
+    /// What a freshly allocated account deserializes to.
+    pub fn uninitialized() -> Self {
+        Self {
+            chain_id: 0,
+            gas_token_price: 0,
+            prices: PricesStatePlatform::Uninitialized([0; PricesStatePlatform::SIZE]),
+            precision: 0,
+        }
+    }
+
+    /// The prices that the fees are computed with, `None` if uninitialized.
+    pub fn chain_prices(&self) -> Option<ChainPrices> {
+        let platform = match self.prices {
+            PricesStatePlatform::Evm {
+                gas_price,
+                price_per_tx_byte,
+            } => PlatformPrices::Evm {
+                gas_price,
+                price_per_tx_byte,
+            },
+            PricesStatePlatform::Sui {
+                computation_unit_price,
+                byte_price,
+                rebate_ratio,
+            } => PlatformPrices::Sui {
+                computation_unit_price,
+                byte_price,
+                rebate_ratio,
+            },
+            PricesStatePlatform::Uninitialized(_) => return None,
+        };
+
+        Some(ChainPrices {
+            gas_token_price: self.gas_token_price,
+            platform,
+            precision: self.precision,
+        })
+    }
+
+    /// Returns the same prices expressed with the given precision. Lowering the precision
+    /// truncates the prices.
+    pub fn with_precision(&self, precision: u8) -> Result<Self> {
+        require!(
+            precision <= MAX_PRICE_PRECISION,
+            PriceOracleError::InvalidPrecision
+        );
+
+        let factor = 10_u64.pow(precision.abs_diff(self.precision).into());
+        let rescale = |price: u64| {
+            if precision >= self.precision {
+                int_to_u64(Int::Ok(price) * factor)
+            } else {
+                int_to_u64(Int::Ok(price) / factor)
+            }
+        };
+        let rescale_u32 = |price: u32| -> Result<u32> {
+            u32::try_from(rescale(price.into())?).map_err(|_| PriceOracleError::Overflow.into())
+        };
+
+        let prices = match self.prices {
+            PricesStatePlatform::Evm {
+                gas_price,
+                price_per_tx_byte,
+            } => PricesStatePlatform::Evm {
+                gas_price: rescale_u32(gas_price)?,
+                price_per_tx_byte: rescale_u32(price_per_tx_byte)?,
+            },
+            PricesStatePlatform::Sui {
+                computation_unit_price,
+                byte_price,
+                rebate_ratio,
+            } => PricesStatePlatform::Sui {
+                computation_unit_price: rescale_u32(computation_unit_price)?,
+                byte_price: rescale_u32(byte_price)?,
+                rebate_ratio,
+            },
+            uninitialized @ PricesStatePlatform::Uninitialized(_) => uninitialized,
+        };
+
+        Ok(Self {
+            chain_id: self.chain_id,
+            gas_token_price: rescale(self.gas_token_price)?,
+            prices,
+            precision,
+        })
+    }
+
     pub fn micro_gas_token_to_micro_usd(&self, micro_gas_token: u32) -> Result<u64> {
-        int_to_u64(Int::Ok(self.gas_token_price) * micro_gas_token / MICRO_GAS_TOKEN_PER_GAS_TOKEN)
+        int_to_u64(
+            self.initialized_chain_prices()?
+                .micro_gas_token_to_micro_usd(micro_gas_token),
+        )
     }
 
     pub fn calc_total_fee_micro_usd(
@@ -224,8 +478,8 @@ impl PricesState {
         flat_fee_micro_token: u32,
         flat_fee_micro_usd: u32,
     ) -> Result<u64> {
-        int_to_u64(self.internal_calc_total_fee_micro_usd(
-            config,
+        int_to_u64(self.initialized_chain_prices()?.total_fee_micro_usd(
+            &(*config).into(),
             flat_fee_micro_token,
             flat_fee_micro_usd,
         ))
@@ -246,69 +500,19 @@ impl PricesState {
         flat_fee_micro_token: u32,
         flat_fee_micro_usd: u32,
     ) -> Result<u64> {
-        let total_fees_micro_usd = self.internal_calc_total_fee_micro_usd(
-            config,
+        let total_fees_usd18 = self.initialized_chain_prices()?.total_fee_usd18(
+            &(*config).into(),
             flat_fee_micro_token,
             flat_fee_micro_usd,
         );
 
-        oracle_config.micro_usd_to_sol(int_to_u64(total_fees_micro_usd)?)
+        oracle_config.usd18_to_sol(total_fees_usd18)
     }
 
-    fn internal_calc_total_fee_micro_usd(
-        &self,
-        config: &TargetChainsConfig,
-        flat_fee_micro_token: u32,
-        flat_fee_micro_usd: u32,
-    ) -> Int<u64> {
-        let flat_fee_micro_token = u64::from(flat_fee_micro_token);
-        let flat_fee_micro_usd = u64::from(flat_fee_micro_usd);
-        (match self.prices {
-            PricesStatePlatform::Evm {
-                gas_price,
-                price_per_tx_byte,
-            } => {
-                // Mwei = gas * Mwei/gas + bytes * Mwei/byte + µToken * Mwei/µToken
-                let total_fees_mwei = Int::Ok(config.evm_transaction_gas) * gas_price
-                    + Int::Ok(config.evm_transaction_size) * price_per_tx_byte
-                    + Int::Ok(flat_fee_micro_token) * MWEI_PER_MICRO_ETH;
-
-                // μusd = Mwei * μusd/Token / Mwei/Token
-                total_fees_mwei.mul_div(self.gas_token_price, MWEI_PER_ETH)
-            }
-            PricesStatePlatform::Sui {
-                computation_unit_price,
-                byte_price,
-                rebate_ratio,
-            } => {
-                let bytes_ratio = {
-                    let rebate =
-                        u64::from(config.sui_deleted_bytes) * u64::from(rebate_ratio) / 100_u64;
-                    let stored_bytes = u64::from(config.sui_stored_bytes);
-
-                    if rebate > stored_bytes {
-                        Int::Ok(0)
-                    } else {
-                        Int::Ok(stored_bytes) - Int::Ok(rebate)
-                    }
-                };
-
-                // MIST = CU * MIST/CU
-                let computation_fee =
-                    Int::Ok(config.sui_computation_units) * computation_unit_price;
-                // MIST = bytes * MIST/byte
-                let storage_fee = bytes_ratio * byte_price;
-
-                let total_fees_mist: Int<u64> =
-                    computation_fee + storage_fee + (flat_fee_micro_token * 1_000);
-
-                // μusd = MIST * μusd/SUI / MIST/SUI
-                total_fees_mist.at_least(SUI_MIN_TRANSACTION_COST_MIST) * self.gas_token_price
-                    / MIST_PER_SUI
-            }
-            // This should never happen, because the account is initialized as soon as allocated.
-            PricesStatePlatform::Uninitialized(_) => panic!("Uninitialized prices"),
-        }) + Int::Ok(flat_fee_micro_usd)
+    fn initialized_chain_prices(&self) -> Result<ChainPrices> {
+        Ok(self
+            .chain_prices()
+            .ok_or(PriceOracleError::UninitializedPrices)?)
     }
 }
 
@@ -329,6 +533,18 @@ pub struct TargetChainsConfig {
     pub sui_deleted_bytes: u32,
 }
 
+impl From<TargetChainsConfig> for TargetChainCosts {
+    fn from(config: TargetChainsConfig) -> Self {
+        TargetChainCosts {
+            evm_transaction_gas: config.evm_transaction_gas,
+            evm_transaction_size: config.evm_transaction_size,
+            sui_computation_units: config.sui_computation_units,
+            sui_stored_bytes: config.sui_stored_bytes,
+            sui_deleted_bytes: config.sui_deleted_bytes,
+        }
+    }
+}
+
 #[test]
 fn total_fee_calculation_for_evm_works() {
     let prices = PricesState {
@@ -338,6 +554,7 @@ fn total_fee_calculation_for_evm_works() {
             gas_price: 30,      // 30 Mwei/gas
             price_per_tx_byte: 16, // 16 Mwei/byte
         },
+        precision: 0,
     };
 
     let oracle_config = PriceOracleConfigState {
@@ -371,11 +588,13 @@ fn total_fee_calculation_for_evm_works() {
     // 2. Byte cost in Mwei: 1_000 * 16 = 16_000
     // 3. Flat fee in Mwei: 1_000 * 1_000_000 = 1_000_000_000
     // 4. Total Mwei: 1_003_016_000
-    // 5. Convert to µUSD: 1_003_016_000 * 2_000_000 / 1_000_000_000_000 = 2_006
-    // 6. Add flat fee in µUSD: 2_006 + 500_000 = 502_006
-    // 7. Convert to lamports: 502_006 * LAMPORTS_PER_SOL / 20_000_000 = 25_100_300
+    // 5. Convert to USD18: 1_003_016_000 * 2_000_000 * 1 = 2_006_032_000_000_000
+    // 6. Add flat fee in USD18: 2_006_032_000_000_000 + 500_000 * 10^12 = 502_006_032_000_000_000
+    // 7. Convert to lamports, rounding up:
+    //    502_006_032_000_000_000 * LAMPORTS_PER_SOL / (20_000_000 * 10^12) = 25_100_301.6
+    //    -> 25_100_302
 
-    assert_eq!(fee, 25_100_300);
+    assert_eq!(fee, 25_100_302);
 }
 
 #[test]
@@ -388,6 +607,7 @@ fn total_fee_calculation_for_sui_works() {
             byte_price: 100,             // 100 MIST per byte
             rebate_ratio: 95,            // 95% storage rebate
         },
+        precision: 0,
     };
 
     let oracle_config = PriceOracleConfigState {
@@ -419,12 +639,203 @@ fn total_fee_calculation_for_sui_works() {
     // Expected calculation:
     // 1. Storage bytes after rebate: 1_000 - (100 * 95 / 100) = 905 bytes
     // 2. Computation cost in MIST: 1_000 * 100 = 100_000
-    // 3. Storage cost in MIST: 985 * 100 = 98_500
+    // 3. Storage cost in MIST: 905 * 100 = 90_500
     // 4. Flat fee in MIST: 1_000 * 1_000 = 1_000_000
-    // 5. Total MIST (max with min tx cost): max(1_198_500, 2_000) = 1_198_500
-    // 6. Convert to µUSD: 1_198_500 * 500_000 / 1_000_000_000 = 599
-    // 7. Add flat fee in µUSD: 599 + 500_000 = 500_599
-    // 8. Convert to lamports: 500_599 * LAMPORTS_PER_SOL / 20_000_000 = 25_029_950
+    // 5. Total MIST (max with min tx cost): max(1_190_500, 2_000) = 1_190_500
+    // 6. Convert to USD18: 1_190_500 * 500_000 * 1_000 = 595_250_000_000_000
+    // 7. Add flat fee in USD18: 595_250_000_000_000 + 500_000 * 10^12 = 500_595_250_000_000_000
+    // 8. Convert to lamports, rounding up:
+    //    500_595_250_000_000_000 * LAMPORTS_PER_SOL / (20_000_000 * 10^12) = 25_029_762.5
+    //    -> 25_029_763
+
+    assert_eq!(fee, 25_029_763);
+}
 
-    assert_eq!(fee, 25_029_750);
+#[test]
+fn precision_keeps_cheap_gas_prices() {
+    let config = TargetChainsConfig {
+        evm_transaction_gas: 100_000, // 100k gas
+        evm_transaction_size: 0,
+        sui_computation_units: 0,
+        sui_stored_bytes: 0,
+        sui_deleted_bytes: 0,
+    };
+
+    let prices = PricesState {
+        chain_id: crate::utils::ETHEREUM_CHAIN_ID,
+        gas_token_price: 3_000_000_000_000, // 3000 USD per ETH, in 10⁻⁹usd
+        prices: PricesStatePlatform::Evm {
+            gas_price: 1_200, // 1.2 Mwei/gas, in Kwei
+            price_per_tx_byte: 0,
+        },
+        precision: 3,
+    };
+
+    // 100_000 * 1_200 * 3_000_000_000_000 * 1 / 10^6 = 360 * 10^12 USD18
+    assert_eq!(prices.calc_total_fee_micro_usd(&config, 0, 0).unwrap(), 360);
+    assert_eq!(
+        prices.micro_gas_token_to_micro_usd(1_000).unwrap(),
+        3_000_000
+    );
+
+    // Without the extra decimals the gas price is truncated to 1 Mwei/gas:
+    let truncated = prices.with_precision(0).unwrap();
+    assert_eq!(truncated.gas_token_price, 3_000_000_000);
+    assert!(matches!(
+        truncated.prices,
+        PricesStatePlatform::Evm { gas_price: 1, .. }
+    ));
+    assert_eq!(
+        truncated.calc_total_fee_micro_usd(&config, 0, 0).unwrap(),
+        300
+    );
+
+    let rescaled = truncated.with_precision(3).unwrap();
+    assert_eq!(rescaled.gas_token_price, prices.gas_token_price);
+    assert!(prices.with_precision(MAX_PRICE_PRECISION + 1).is_err());
+
+    // The precision is stored in the last byte of the account:
+    let mut data = Vec::new();
+    prices.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), 8 + PricesState::INIT_SPACE);
+    assert_eq!(data.last(), Some(&3));
+    let deserialized = PricesState::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(deserialized.precision, 3);
+}
+
+#[test]
+fn zeroed_prices_are_rejected() {
+    let mut data = Vec::new();
+    PricesState::uninitialized()
+        .try_serialize(&mut data)
+        .unwrap();
+    assert_eq!(data.len(), 8 + PricesState::INIT_SPACE);
+
+    // What `init` sees right after allocation:
+    let prices = PricesState::try_deserialize_unchecked(&mut data.as_slice()).unwrap();
+    assert_eq!(prices.chain_id, 0);
+    assert_eq!(
+        prices.micro_gas_token_to_micro_usd(1_000_000),
+        Err(PriceOracleError::UninitializedPrices.into())
+    );
+
+    assert_eq!(
+        PricesState::try_deserialize(&mut data.as_slice()).err(),
+        Some(PriceOracleError::UninitializedPrices.into())
+    );
+}
+
+#[test]
+fn zero_copy_layout_matches_the_account() {
+    assert_eq!(
+        std::mem::size_of::<ZeroCopyPricesState>(),
+        PricesState::INIT_SPACE
+    );
+
+    let prices = PricesState {
+        chain_id: crate::utils::SUI_CHAIN_ID,
+        gas_token_price: 3_500_000,
+        prices: PricesStatePlatform::Sui {
+            computation_unit_price: 750,
+            byte_price: 7_600,
+            rebate_ratio: 99,
+        },
+        precision: 2,
+    };
+    let mut data = Vec::new();
+    prices.try_serialize(&mut data).unwrap();
+
+    let zero_copy: &ZeroCopyPricesState = bytemuck::from_bytes(&data[8..]);
+    assert_eq!(zero_copy.chain_id(), crate::utils::SUI_CHAIN_ID);
+    let decoded = zero_copy.prices_state().unwrap();
+    assert_eq!(decoded.gas_token_price, 3_500_000);
+    assert_eq!(decoded.precision, 2);
+    assert!(matches!(
+        decoded.prices,
+        PricesStatePlatform::Sui {
+            computation_unit_price: 750,
+            byte_price: 7_600,
+            rebate_ratio: 99,
+        }
+    ));
+
+    assert_eq!(
+        ZeroCopyPricesState::zeroed().prices_state().err(),
+        Some(PriceOracleError::UninitializedPrices.into())
+    );
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn prices_states() -> impl Strategy<Value = PricesState> {
+        let platform = prop_oneof![
+            any::<[u8; PricesStatePlatform::SIZE]>().prop_map(PricesStatePlatform::Uninitialized),
+            (any::<u32>(), any::<u32>()).prop_map(|(gas_price, price_per_tx_byte)| {
+                PricesStatePlatform::Evm {
+                    gas_price,
+                    price_per_tx_byte,
+                }
+            }),
+            (any::<u32>(), any::<u32>(), any::<u8>()).prop_map(
+                |(computation_unit_price, byte_price, rebate_ratio)| PricesStatePlatform::Sui {
+                    computation_unit_price,
+                    byte_price,
+                    rebate_ratio,
+                }
+            ),
+        ];
+        (
+            any::<u16>(),
+            any::<u64>(),
+            platform,
+            0..=MAX_PRICE_PRECISION,
+        )
+            .prop_map(
+                |(chain_id, gas_token_price, prices, precision)| PricesState {
+                    chain_id,
+                    gas_token_price,
+                    prices,
+                    precision,
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn fee_calculation_never_panics(
+            prices in prices_states(),
+            sol_price: u64,
+            config in any::<[u32; 5]>(),
+            flat_fee_micro_token: u32,
+            flat_fee_micro_usd: u32,
+        ) {
+            let oracle_config = PriceOracleConfigState {
+                sol_price,
+                owner: Pubkey::default(),
+                pending_owner: None,
+            };
+            let config = TargetChainsConfig {
+                evm_transaction_gas: config[0],
+                evm_transaction_size: config[1],
+                sui_computation_units: config[2],
+                sui_stored_bytes: config[3],
+                sui_deleted_bytes: config[4],
+            };
+
+            let micro_usd = prices.calc_total_fee_micro_usd(&config, flat_fee_micro_token, flat_fee_micro_usd);
+            let lamports =
+                prices.calculate_total_fee(&oracle_config, &config, flat_fee_micro_token, flat_fee_micro_usd);
+            let dropoff_micro_usd = prices.micro_gas_token_to_micro_usd(flat_fee_micro_token);
+
+            if let PricesStatePlatform::Uninitialized(_) = prices.prices {
+                let uninitialized = || Some(PriceOracleError::UninitializedPrices.into());
+                prop_assert_eq!(micro_usd.err(), uninitialized());
+                prop_assert_eq!(lamports.err(), uninitialized());
+                prop_assert_eq!(dropoff_micro_usd.err(), uninitialized());
+            }
+        }
+    }
 }
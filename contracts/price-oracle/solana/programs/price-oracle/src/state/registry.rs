@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+/// A badge holder, as listed in the [`RoleRegistryState`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RoleMember {
+    pub address: Pubkey,
+    pub is_admin: bool,
+}
+
+/// Lists all badge holders and their roles, so that they can be enumerated without scanning the
+/// program accounts. The account is reallocated as members come and go.
+///
+/// The badges remain the source of truth for authorization, in particular for their expiration.
+#[account]
+pub struct RoleRegistryState {
+    pub members: Vec<RoleMember>,
+}
+
+impl RoleRegistryState {
+    /// Value `b"role_registry"`.
+    pub const SEED_PREFIX: &'static [u8] = b"role_registry";
+
+    /// The account size (discriminator included) for a registry of `member_count` members.
+    pub fn space(member_count: usize) -> usize {
+        8 + 4 + member_count * RoleMember::INIT_SPACE
+    }
+
+    pub fn contains(&self, address: &Pubkey) -> bool {
+        self.members.iter().any(|member| member.address == *address)
+    }
+
+    /// The account size once `address` is inserted.
+    pub fn space_with(&self, address: &Pubkey) -> usize {
+        Self::space(self.members.len() + usize::from(!self.contains(address)))
+    }
+
+    /// The account size once `address` is removed.
+    pub fn space_without(&self, address: &Pubkey) -> usize {
+        Self::space(self.members.len() - usize::from(self.contains(address)))
+    }
+
+    /// The account size once the owner role is transferred from `old_owner` to `new_owner`.
+    pub fn space_with_owner(&self, old_owner: &Pubkey, new_owner: &Pubkey) -> usize {
+        Self::space(
+            self.members.len() + usize::from(!self.contains(new_owner))
+                - usize::from(self.contains(old_owner)),
+        )
+    }
+
+    /// Inserts a member, or updates its role if it is already listed.
+    pub fn insert(&mut self, address: Pubkey, is_admin: bool) {
+        match self
+            .members
+            .iter_mut()
+            .find(|member| member.address == address)
+        {
+            Some(member) => member.is_admin = is_admin,
+            None => self.members.push(RoleMember { address, is_admin }),
+        }
+    }
+
+    pub fn remove(&mut self, address: &Pubkey) {
+        self.members.retain(|member| member.address != *address);
+    }
+}
+
+#[test]
+fn registry_space_follows_members() {
+    let owner = Pubkey::new_unique();
+    let assistant = Pubkey::new_unique();
+    let mut registry = RoleRegistryState { members: vec![] };
+
+    assert_eq!(registry.space_with(&owner), RoleRegistryState::space(1));
+    registry.insert(owner, true);
+    assert_eq!(registry.space_with(&owner), RoleRegistryState::space(1));
+    registry.insert(assistant, false);
+    assert_eq!(
+        registry.try_to_vec().unwrap().len() + 8,
+        RoleRegistryState::space(2)
+    );
+
+    // The assistant becomes the owner:
+    assert_eq!(
+        registry.space_with_owner(&owner, &assistant),
+        RoleRegistryState::space(1)
+    );
+    registry.remove(&owner);
+    registry.insert(assistant, true);
+    assert_eq!(
+        registry.members,
+        vec![RoleMember {
+            address: assistant,
+            is_admin: true
+        }]
+    );
+
+    assert_eq!(registry.space_without(&owner), RoleRegistryState::space(1));
+    assert_eq!(
+        registry.space_without(&assistant),
+        RoleRegistryState::space(0)
+    );
+}
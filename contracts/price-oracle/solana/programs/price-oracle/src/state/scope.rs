@@ -0,0 +1,87 @@
+use crate::{state::AuthBadgeState, utils::Platform};
+use anchor_lang::prelude::*;
+
+/// The maximum number of individual chains an assistant can be scoped to.
+pub const MAX_SCOPED_CHAIN_IDS: usize = 16;
+
+/// What an assistant wants to update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceTarget {
+    SolPrice,
+    Chain(u16),
+}
+
+/// Restricts an assistant to a subset of the prices.
+///
+/// Assistants without a scope account may update any prices, as do admins regardless of it.
+/// Since the scope only restricts when it exists, every update has to pass the PDA of its signer,
+/// see [`AssistantScopeState::permits`].
+#[account]
+#[derive(InitSpace)]
+pub struct AssistantScopeState {
+    pub assistant: Pubkey,
+
+    /// Whether the assistant may update the SOL price.
+    pub sol_price: bool,
+
+    /// The platforms of which the assistant may update all chains, see [`Platform::mask`].
+    pub platforms: u8,
+
+    /// Individual chains the assistant may update, in addition to the ones of `platforms`.
+    #[max_len(MAX_SCOPED_CHAIN_IDS)]
+    pub chain_ids: Vec<u16>,
+}
+
+impl AssistantScopeState {
+    /// Value `b"scope"`.
+    pub const SEED_PREFIX: &'static [u8] = b"scope";
+
+    pub fn allows(&self, target: PriceTarget) -> bool {
+        match target {
+            PriceTarget::SolPrice => self.sol_price,
+            PriceTarget::Chain(chain_id) => {
+                Platform::from_chain_id(chain_id)
+                    .is_some_and(|platform| self.platforms & platform.mask() != 0)
+                    || self.chain_ids.contains(&chain_id)
+            }
+        }
+    }
+
+    /// Whether the holder of `badge` may update `target`, given the (possibly nonexistent) scope
+    /// account derived from the badge's address.
+    pub fn permits(badge: &AuthBadgeState, scope: &AccountInfo, target: PriceTarget) -> bool {
+        if badge.is_admin || scope.data_is_empty() {
+            return true;
+        }
+
+        let Ok(data) = scope.try_borrow_data() else {
+            return false;
+        };
+        Self::try_deserialize(&mut &data[..]).is_ok_and(|scope| scope.allows(target))
+    }
+}
+
+#[test]
+fn scope_allows_platforms_and_individual_chains() {
+    use crate::utils::{ETHEREUM_CHAIN_ID, SOLANA_CHAIN_ID, SUI_CHAIN_ID};
+    const ARBITRUM_CHAIN_ID: u16 = 23;
+
+    let mut scope = AssistantScopeState {
+        assistant: Pubkey::new_unique(),
+        sol_price: false,
+        platforms: Platform::Evm.mask(),
+        chain_ids: vec![],
+    };
+    assert!(scope.allows(PriceTarget::Chain(ETHEREUM_CHAIN_ID)));
+    assert!(scope.allows(PriceTarget::Chain(ARBITRUM_CHAIN_ID)));
+    assert!(!scope.allows(PriceTarget::Chain(SUI_CHAIN_ID)));
+    assert!(!scope.allows(PriceTarget::Chain(SOLANA_CHAIN_ID)));
+    assert!(!scope.allows(PriceTarget::SolPrice));
+
+    scope.platforms = 0;
+    scope.chain_ids = vec![SUI_CHAIN_ID];
+    scope.sol_price = true;
+    assert!(!scope.allows(PriceTarget::Chain(ETHEREUM_CHAIN_ID)));
+    assert!(scope.allows(PriceTarget::Chain(SUI_CHAIN_ID)));
+    assert!(scope.allows(PriceTarget::SolPrice));
+}
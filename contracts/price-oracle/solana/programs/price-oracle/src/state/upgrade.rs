@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// How long a proposed upgrade must wait before it can be executed.
+#[account]
+#[derive(InitSpace)]
+pub struct UpgradeTimelockState {
+    /// In seconds.
+    pub delay: u32,
+}
+
+impl UpgradeTimelockState {
+    /// Value `b"upgrade_timelock"`.
+    pub const SEED_PREFIX: &'static [u8] = b"upgrade_timelock";
+}
+
+/// An upgrade proposed by the owner, see [`crate::processor::propose_upgrade`].
+///
+/// There is at most one pending upgrade at a time.
+#[account]
+#[derive(InitSpace)]
+pub struct UpgradeProposalState {
+    /// The buffer holding the new program. Its authority is the upgrade lock until the proposal
+    /// is executed or cancelled, so that nobody can write to it in the meantime.
+    pub buffer: Pubkey,
+
+    /// The SHA-256 hash of the program in the buffer, excluding the buffer metadata.
+    pub buffer_hash: [u8; 32],
+
+    /// Unix timestamp from which anyone can execute the upgrade.
+    pub executable_at: i64,
+}
+
+impl UpgradeProposalState {
+    /// Value `b"upgrade_proposal"`.
+    pub const SEED_PREFIX: &'static [u8] = b"upgrade_proposal";
+}
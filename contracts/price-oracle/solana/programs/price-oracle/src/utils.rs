@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use std::fmt;
 use crate::int::Int;
+use fee_math::FeeError;
 use crate::error::PriceOracleError;
 
 pub const SOLANA_CHAIN_ID: u16 = 1;
@@ -21,6 +22,11 @@ impl fmt::Display for Platform {
 }
 
 impl Platform {
+    /// The bit of the platform in a set of platforms.
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
     pub fn from_chain_id(chain_id: u16) -> Option<Self> {
         match chain_id {
             0 => None,
@@ -31,12 +37,15 @@ impl Platform {
     }
 }
 
-pub fn int_to_u64(val: Int<u64>) -> Result<u64> {
-    match val {
-        Int::Ok(val) => Ok(val),
-        Int::Overflow => Err(PriceOracleError::Overflow.into()),
-        Int::DivisionByZero => Err(PriceOracleError::DivisionByZero.into()),
-    }
+pub fn int_to_u64<T>(val: Int<T>) -> Result<u64>
+where
+    u64: TryFrom<T>,
+{
+    fee_result(val.narrow().into_result())
+}
+
+pub fn fee_result<T>(result: std::result::Result<T, FeeError>) -> Result<T> {
+    Ok(result.map_err(PriceOracleError::from)?)
 }
 
 /// Empties the account balance to the provided recipient.
@@ -65,3 +74,49 @@ impl<'info> DrainAccount<'info> {
         Ok(())
     }
 }
+
+/// Grows an account to `new_len` bytes, topping up its balance from `payer` so that it stays
+/// rent exempt. Does nothing if the account is already large enough.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let missing_lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if missing_lamports != 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            missing_lamports,
+        )?;
+    }
+
+    account.resize(new_len)?;
+
+    Ok(())
+}
+
+/// Closes an account that isn't deserialized by anchor (which would otherwise take care of it),
+/// sending its balance to `recipient`.
+pub fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    let lamports = account.lamports();
+    **recipient.try_borrow_mut_lamports()? += lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.assign(&System::id());
+    account.resize(0)?;
+
+    Ok(())
+}
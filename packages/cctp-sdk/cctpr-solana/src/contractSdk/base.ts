@@ -87,6 +87,7 @@ export class CctpRBase<N extends Network> {
     if (cached)
       return cached;
 
+    //derived from the 4 byte big endian domain (the legacy ones used a single byte)
     const chainConfigPda = findPda(
       ["chain_config", serialize(foreignDomainItem(this.network), domain as TODO)],
       this.address,
@@ -5,12 +5,14 @@
 
 import type { DeriveType, Layout } from "binary-layout";
 import { enumItem, optionItem } from "binary-layout";
-import { mapTo, zip, fromEntries, valueIndexEntries } from "@stable-io/map-utils";
+import { mapTo, range, zip, fromEntries, valueIndexEntries } from "@stable-io/map-utils";
 import type { Network } from "@stable-io/cctp-sdk-definitions";
 import {
   Usdc,
   Sol,
   Percentage,
+  usdc,
+  percentage,
   domainItem,
   universalAddressItem,
   amountItem,
@@ -33,13 +35,18 @@ import {
   bumpItem,
   solanaAddressItem,
   vecBytesItem,
+  vecArrayItem,
 } from "@stable-io/cctp-sdk-solana";
 import { chainItem as oracleChainItem } from "./oracleLayouts.js";
 import { foreignDomains } from "./constants.js";
 
+//big endian by default, which is how the chain config PDA seeds encode it
 export const foreignDomainItem = <N extends Network>(network: N) =>
-  ({ ...domainItem(foreignDomains(network)), size: 1 } as const);
+  domainItem(foreignDomains(network));
 const rawEvmAddressItem = { binary: "bytes", size: 20 } as const;
+//accounts with any other version have to be migrated first
+const layoutVersionItem = (version: number) =>
+  ({ binary: "uint", size: 1, custom: version, omit: true } as const);
 
 const feeAdjustmentLayout = littleEndian([
   { name: "absolute", ...amountItem(4, Usdc), binary: "int" },
@@ -72,21 +79,25 @@ export const configLayout =
 export type Config =
   DeriveType<typeof configLayout>;
 
-const feeAdjustmentsArrayLayout = {
-  binary: "array",
-  length: feeAdjustmentTypes.length,
-  layout: feeAdjustmentLayout,
-} as const satisfies Layout;
+const noFeeAdjustment = { absolute: usdc(0), relative: percentage(0) } as const;
 
+//a vec so that adding a fee adjustment type only requires growing existing accounts, the types
+//  that were added after an account was last migrated aren't set
 const feeAdjustmentsItem = transform(
-  feeAdjustmentsArrayLayout,
-  derived => fromEntries(zip([feeAdjustmentTypes, derived])),
+  vecArrayItem(feeAdjustmentLayout),
+  derived => fromEntries(zip([
+    feeAdjustmentTypes,
+    mapTo(range(feeAdjustmentTypes.length))(i => derived[i] ?? noFeeAdjustment),
+  ])),
   transformed => mapTo(feeAdjustmentTypes)(t => transformed[t]),
 ) satisfies Layout;
 export type FeeAdjustments = DeriveType<typeof feeAdjustmentsItem>;
 
+//the legacy chain configs, which stored the domain in a single byte (and were derived from it),
+//  still use the "ChainConfig" discriminator
 export const chainConfigLayout = <N extends Network>(network: N) =>
-  accountLayout("ChainConfig", littleEndian([
+  accountLayout("ChainConfigV2", littleEndian([
+    { name: "version",        ...layoutVersionItem(1)       },
     { name: "domain",         ...foreignDomainItem(network) },
     { name: "oracleChain",    ...oracleChainItem(network)   },
     { name: "feeAdjustments", ...feeAdjustmentsItem         },